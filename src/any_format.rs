@@ -0,0 +1,305 @@
+use std::fmt;
+use std::str::FromStr;
+
+use cosmwasm_std::Uint128;
+
+use schemars::gen::SchemaGenerator;
+use schemars::schema::Schema;
+use schemars::JsonSchema;
+use serde::de::{self, Deserializer, Visitor};
+use serde::{Deserialize, Serialize};
+
+use super::asset::AssetUnchecked;
+use super::asset_info::AssetInfoUnchecked;
+
+/// An `AssetInfoUnchecked` that deserializes from any of the JSON formats used
+/// by common contracts, and serializes in this crate's format
+///
+/// Accepted formats are:
+/// - `{"native":"uosmo"}`, `{"cw20":"addr"}` and `{"cw721":["addr","id"]}`
+///   (this crate)
+/// - `{"native_token":{"denom":"uosmo"}}` and `{"token":{"contract_addr":"addr"}}`
+///   (Astroport and Terraswap)
+/// - `"native:uosmo"`, `"cw20:addr"` and `"cw721:addr:id"` (cw-asset strings)
+/// - bare strings, e.g. `"uosmo"`, which are read as native denoms
+///
+/// NOTE: A bare string can not be told apart from a CW20 address without an
+/// `Api`, so it is always read as a native denom. Use `AssetInfo::from_str` on
+/// the raw string instead if it may be an address.
+///
+/// **Usage:**
+/// The following code accepts an asset info in any format in a message field.
+///
+/// ```rust
+/// #[cw_serde]
+/// pub struct ExecuteMsg {
+///     #[serde(deserialize_with = "deserialize_asset_info_any_format")]
+///     pub offer: AssetInfoUnchecked,
+/// }
+/// ```
+#[derive(Serialize, Clone, Debug, PartialEq, Eq)]
+#[serde(transparent)]
+pub struct AssetInfoAnyFormat(pub AssetInfoUnchecked);
+
+/// An `AssetUnchecked` that deserializes from any of the JSON formats used by
+/// common contracts, and serializes in this crate's format
+///
+/// Accepted formats are `{"info":..,"amount":..}` with an info in any format
+/// accepted by `AssetInfoAnyFormat`, as well as SDK coins `{"denom":..,"amount":..}`
+/// and CW20 coins `{"address":..,"amount":..}`. Amounts can be strings or
+/// numbers.
+///
+/// NOTE: The JSON deserializer of CosmWasm reads numbers of unknown type as
+/// `u64`, and never as floats, so amounts above `u64::MAX` must be strings.
+#[derive(Serialize, Clone, Debug, PartialEq, Eq)]
+#[serde(transparent)]
+pub struct AssetAnyFormat(pub AssetUnchecked);
+
+impl From<AssetInfoAnyFormat> for AssetInfoUnchecked {
+    fn from(info: AssetInfoAnyFormat) -> Self {
+        info.0
+    }
+}
+
+impl From<AssetInfoUnchecked> for AssetInfoAnyFormat {
+    fn from(info: AssetInfoUnchecked) -> Self {
+        Self(info)
+    }
+}
+
+impl From<AssetAnyFormat> for AssetUnchecked {
+    fn from(asset: AssetAnyFormat) -> Self {
+        asset.0
+    }
+}
+
+impl From<AssetUnchecked> for AssetAnyFormat {
+    fn from(asset: AssetUnchecked) -> Self {
+        Self(asset)
+    }
+}
+
+/// Deserialize an `AssetInfoUnchecked` in any format accepted by
+/// `AssetInfoAnyFormat`, for use with `#[serde(deserialize_with)]`
+pub fn deserialize_asset_info_any_format<'de, D: Deserializer<'de>>(
+    deserializer: D,
+) -> Result<AssetInfoUnchecked, D::Error> {
+    AssetInfoAnyFormat::deserialize(deserializer).map(Into::into)
+}
+
+/// Deserialize an `AssetUnchecked` in any format accepted by `AssetAnyFormat`,
+/// for use with `#[serde(deserialize_with)]`
+pub fn deserialize_asset_any_format<'de, D: Deserializer<'de>>(
+    deserializer: D,
+) -> Result<AssetUnchecked, D::Error> {
+    AssetAnyFormat::deserialize(deserializer).map(Into::into)
+}
+
+#[derive(Deserialize)]
+#[serde(untagged)]
+enum AnyAssetInfo {
+    Canonical(AssetInfoUnchecked),
+    Pair(PairAssetInfo),
+    String(String),
+}
+
+// the asset info format of Astroport and Terraswap
+#[derive(Deserialize)]
+#[serde(rename_all = "snake_case")]
+enum PairAssetInfo {
+    NativeToken { denom: String },
+    Token { contract_addr: String },
+}
+
+impl<'de> Deserialize<'de> for AssetInfoAnyFormat {
+    fn deserialize<D: Deserializer<'de>>(deserializer: D) -> Result<Self, D::Error> {
+        let info = match AnyAssetInfo::deserialize(deserializer)? {
+            AnyAssetInfo::Canonical(info) => info,
+            AnyAssetInfo::Pair(PairAssetInfo::NativeToken { denom }) => {
+                AssetInfoUnchecked::native(denom)
+            }
+            AnyAssetInfo::Pair(PairAssetInfo::Token { contract_addr }) => {
+                AssetInfoUnchecked::cw20(contract_addr)
+            }
+            AnyAssetInfo::String(s) => parse_asset_info(&s).map_err(de::Error::custom)?,
+        };
+        Ok(Self(info))
+    }
+}
+
+fn parse_asset_info(s: &str) -> Result<AssetInfoUnchecked, String> {
+    let invalid = || format!("invalid asset info: {:?}", s);
+    if s.is_empty() {
+        return Err(invalid());
+    }
+    Ok(match s.split_once(':') {
+        Some(("native", denom)) if !denom.is_empty() => AssetInfoUnchecked::native(denom),
+        Some(("cw20", contract_addr)) if !contract_addr.is_empty() => {
+            AssetInfoUnchecked::cw20(contract_addr)
+        }
+        Some(("cw721", nft)) => match nft.split_once(':') {
+            Some((contract_addr, token_id))
+                if !contract_addr.is_empty() && !token_id.is_empty() =>
+            {
+                AssetInfoUnchecked::cw721(contract_addr, token_id)
+            }
+            _ => return Err(invalid()),
+        },
+        Some(("native" | "cw20", _)) => return Err(invalid()),
+        _ => AssetInfoUnchecked::native(s),
+    })
+}
+
+#[derive(Deserialize)]
+#[serde(untagged)]
+enum AnyAsset {
+    Asset {
+        info: AssetInfoAnyFormat,
+        #[serde(deserialize_with = "deserialize_amount")]
+        amount: Uint128,
+    },
+    Coin {
+        denom: String,
+        #[serde(deserialize_with = "deserialize_amount")]
+        amount: Uint128,
+    },
+    Cw20Coin {
+        address: String,
+        #[serde(deserialize_with = "deserialize_amount")]
+        amount: Uint128,
+    },
+}
+
+impl<'de> Deserialize<'de> for AssetAnyFormat {
+    fn deserialize<D: Deserializer<'de>>(deserializer: D) -> Result<Self, D::Error> {
+        let asset = match AnyAsset::deserialize(deserializer)? {
+            AnyAsset::Asset { info, amount } => AssetUnchecked::new(info.into(), amount),
+            AnyAsset::Coin { denom, amount } => AssetUnchecked::native(denom, amount),
+            AnyAsset::Cw20Coin { address, amount } => AssetUnchecked::cw20(address, amount),
+        };
+        Ok(Self(asset))
+    }
+}
+
+fn deserialize_amount<'de, D: Deserializer<'de>>(deserializer: D) -> Result<Uint128, D::Error> {
+    deserializer.deserialize_any(AmountVisitor)
+}
+
+struct AmountVisitor;
+
+impl<'de> Visitor<'de> for AmountVisitor {
+    type Value = Uint128;
+
+    fn expecting(&self, formatter: &mut fmt::Formatter) -> fmt::Result {
+        formatter.write_str("an unsigned integer amount, as a string or a number")
+    }
+
+    fn visit_str<E: de::Error>(self, v: &str) -> Result<Self::Value, E> {
+        Uint128::from_str(v).map_err(|_| E::custom(format!("invalid amount: {:?}", v)))
+    }
+
+    fn visit_u64<E: de::Error>(self, v: u64) -> Result<Self::Value, E> {
+        Ok(Uint128::from(v))
+    }
+}
+
+// the types serialize in this crate's format, which the schemas describe,
+// although they accept more formats
+impl JsonSchema for AssetInfoAnyFormat {
+    fn schema_name() -> String {
+        "AssetInfoAnyFormat".to_string()
+    }
+
+    fn json_schema(gen: &mut SchemaGenerator) -> Schema {
+        AssetInfoUnchecked::json_schema(gen)
+    }
+}
+
+impl JsonSchema for AssetAnyFormat {
+    fn schema_name() -> String {
+        "AssetAnyFormat".to_string()
+    }
+
+    fn json_schema(gen: &mut SchemaGenerator) -> Schema {
+        AssetUnchecked::json_schema(gen)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use cosmwasm_std::{from_json, to_json_vec};
+
+    use test_case::test_case;
+
+    #[test_case(r#"{"native":"uosmo"}"#, AssetInfoUnchecked::native("uosmo") ; "native")]
+    #[test_case(r#"{"cw20":"mock_token"}"#, AssetInfoUnchecked::cw20("mock_token") ; "cw20")]
+    #[test_case(r#"{"cw721":["mock_nft","42"]}"#, AssetInfoUnchecked::cw721("mock_nft", "42") ; "cw721")]
+    #[test_case(r#"{"native_token":{"denom":"uosmo"}}"#, AssetInfoUnchecked::native("uosmo") ; "astroport native")]
+    #[test_case(r#"{"token":{"contract_addr":"mock_token"}}"#, AssetInfoUnchecked::cw20("mock_token") ; "astroport token")]
+    #[test_case(r#""native:uosmo""#, AssetInfoUnchecked::native("uosmo") ; "prefixed native")]
+    #[test_case(r#""cw20:mock_token""#, AssetInfoUnchecked::cw20("mock_token") ; "prefixed cw20")]
+    #[test_case(r#""cw721:mock_nft:42""#, AssetInfoUnchecked::cw721("mock_nft", "42") ; "prefixed cw721")]
+    #[test_case(r#""ibc/27394FB092D2ECCD56123C74F36E4C1F926001CEADA9CA97EA622B25F41E5EB2""#, AssetInfoUnchecked::native("ibc/27394FB092D2ECCD56123C74F36E4C1F926001CEADA9CA97EA622B25F41E5EB2") ; "bare denom")]
+    fn deserializing_asset_info(json: &str, expected: AssetInfoUnchecked) {
+        let info: AssetInfoAnyFormat = from_json(json.as_bytes()).unwrap();
+        assert_eq!(info.0, expected);
+
+        // serializes in this crate's format
+        assert_eq!(to_json_vec(&info).unwrap(), to_json_vec(&expected).unwrap());
+    }
+
+    #[test_case(r#""""# ; "empty string")]
+    #[test_case(r#""cw20:""# ; "empty address")]
+    #[test_case(r#""cw721:mock_nft""# ; "missing token id")]
+    #[test_case(r#"{"cw1155":"mock_token"}"# ; "unknown kind")]
+    #[test_case("42" ; "number")]
+    fn rejecting_asset_info(json: &str) {
+        assert!(from_json::<AssetInfoAnyFormat>(json.as_bytes()).is_err());
+    }
+
+    #[test_case(r#"{"info":{"native":"uosmo"},"amount":"12345"}"#, AssetUnchecked::native("uosmo", 12345u128) ; "canonical")]
+    #[test_case(r#"{"info":{"token":{"contract_addr":"mock_token"}},"amount":12345}"#, AssetUnchecked::cw20("mock_token", 12345u128) ; "astroport with number amount")]
+    #[test_case(r#"{"info":"uosmo","amount":12345}"#, AssetUnchecked::native("uosmo", 12345u128) ; "bare denom")]
+    #[test_case(r#"{"denom":"uosmo","amount":"12345"}"#, AssetUnchecked::native("uosmo", 12345u128) ; "coin")]
+    #[test_case(r#"{"address":"mock_token","amount":"12345"}"#, AssetUnchecked::cw20("mock_token", 12345u128) ; "cw20 coin")]
+    #[test_case(r#"{"denom":"uosmo","amount":"340282366920938463463374607431768211455"}"#, AssetUnchecked::native("uosmo", u128::MAX) ; "string amount above u64")]
+    #[test_case(r#"{"info":"uosmo","amount":18446744073709551615}"#, AssetUnchecked::native("uosmo", u64::MAX) ; "number amount of u64 max")]
+    fn deserializing_asset(json: &str, expected: AssetUnchecked) {
+        let asset: AssetAnyFormat = from_json(json.as_bytes()).unwrap();
+        assert_eq!(asset.0, expected);
+        assert_eq!(
+            to_json_vec(&asset).unwrap(),
+            to_json_vec(&expected).unwrap()
+        );
+    }
+
+    #[test_case(r#"{"info":"uosmo","amount":"abc"}"# ; "invalid amount")]
+    #[test_case(r#"{"info":"uosmo","amount":-1}"# ; "negative amount")]
+    #[test_case(r#"{"info":"uosmo","amount":1.5}"# ; "fractional amount")]
+    #[test_case(r#"{"info":"uosmo","amount":1e3}"# ; "exponent amount")]
+    #[test_case(r#"{"info":"uosmo","amount":18446744073709551616}"# ; "number amount above u64")]
+    #[test_case(r#"{"info":"uosmo"}"# ; "missing amount")]
+    fn rejecting_asset(json: &str) {
+        assert!(from_json::<AssetAnyFormat>(json.as_bytes()).is_err());
+    }
+
+    #[test]
+    fn deserializing_fields() {
+        #[derive(Deserialize)]
+        struct Msg {
+            #[serde(deserialize_with = "deserialize_asset_info_any_format")]
+            ask: AssetInfoUnchecked,
+            #[serde(deserialize_with = "deserialize_asset_any_format")]
+            offer: AssetUnchecked,
+        }
+
+        let msg: Msg = from_json(
+            br#"{"ask":{"native_token":{"denom":"uatom"}},"offer":{"denom":"uosmo","amount":"100"}}"#
+                .as_slice(),
+        )
+        .unwrap();
+        assert_eq!(msg.ask, AssetInfoUnchecked::native("uatom"));
+        assert_eq!(msg.offer, AssetUnchecked::native("uosmo", 100u128));
+    }
+}
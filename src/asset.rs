@@ -1,21 +1,21 @@
 use std::convert::{TryFrom, TryInto};
 use std::fmt;
+use std::str::FromStr;
 
 use cosmwasm_std::{
-    from_binary, to_binary, Addr, Api, BankMsg, Binary, Coin, CosmosMsg, QuerierWrapper, StdError,
-    StdResult, Uint128, WasmMsg,
+    to_json_binary, Addr, Api, Attribute, BankMsg, Binary, Coin, CosmosMsg, StdError, StdResult,
+    Uint128, WasmMsg,
 };
-use cw20::{Cw20Coin, Cw20CoinVerified, Cw20ExecuteMsg, Cw20QueryMsg};
+use cw20::{Cw20Coin, Cw20CoinVerified, Cw20ExecuteMsg};
 
 use schemars::JsonSchema;
 use serde::{Deserialize, Serialize};
 
-#[cfg(feature = "terra")]
-use {cosmwasm_std::QuerierWrapper, terra_cosmwasm::TerraQuerier};
-
-use crate::AssetInfoUnchecked;
+use crate::{AssetError, AssetInfoUnchecked, AssetQuerier, DecimalsProvider, PriceSource};
 
 use super::asset_info::{AssetInfo, AssetInfoBase};
+use super::cw721::Cw721ExecuteMsg;
+use super::events::{attribute_key, get_attribute};
 
 #[derive(Serialize, Deserialize, Clone, Debug, PartialEq, Eq, JsonSchema)]
 pub struct AssetBase<T> {
@@ -57,15 +57,92 @@ impl AssetUnchecked {
         }
     }
 
+    pub fn cw721<A: Into<String>, B: Into<String>>(contract_addr: A, token_id: B) -> Self {
+        Self {
+            info: AssetInfoUnchecked::cw721(contract_addr, token_id),
+            amount: Uint128::one(),
+        }
+    }
+
     /// Validate contract address (if any) and returns a new `Asset` instance
+    ///
+    /// Returns an error if the amount of a CW721 NFT is neither 0 nor 1.
     pub fn check(&self, api: &dyn Api) -> StdResult<Asset> {
-        Ok(Asset {
+        let asset = Asset {
             info: self.info.check(api)?,
             amount: self.amount,
+        };
+        asset.check_nft_amount()?;
+        Ok(asset)
+    }
+
+    /// Re-encode a CW20 token's bech32 address with the given prefix. Native
+    /// coins are returned unchanged.
+    pub fn with_bech32_prefix(&self, prefix: &str) -> Result<Self, AssetError> {
+        Ok(Self {
+            info: self.info.with_bech32_prefix(prefix)?,
+            amount: self.amount,
+        })
+    }
+
+    /// Validate like `check`, and additionally verify that a CW20 token's
+    /// address belongs to a CW20 contract
+    pub fn check_with_querier(
+        &self,
+        api: &dyn Api,
+        querier: &dyn AssetQuerier,
+    ) -> Result<Asset, AssetError> {
+        Ok(Asset {
+            info: self.info.check_with_querier(api, querier)?,
+            amount: self.amount,
+        })
+    }
+
+    /// Create a new `AssetBase` instance from a human readable amount, i.e. in
+    /// whole units of an asset with the given number of decimals
+    ///
+    /// Returns an error if the amount is not a non-negative decimal number, or
+    /// if it has more fractional digits than the asset's decimals.
+    ///
+    /// **Usage:**
+    /// The following code creates an asset of 1500000 uosmo.
+    ///
+    /// ```rust
+    /// let asset = AssetUnchecked::from_human(AssetInfoUnchecked::native("uosmo"), "1.5", 6)?;
+    /// ```
+    pub fn from_human(info: AssetInfoUnchecked, amount: &str, decimals: u8) -> StdResult<Self> {
+        let invalid = || StdError::generic_err(format!("invalid amount: {}", amount));
+
+        let (whole, fraction) = amount.split_once('.').unwrap_or((amount, ""));
+        if whole.is_empty() || !whole.chars().all(|c| c.is_ascii_digit()) {
+            return Err(invalid());
+        }
+        if (amount.contains('.') && fraction.is_empty())
+            || !fraction.chars().all(|c| c.is_ascii_digit())
+        {
+            return Err(invalid());
+        }
+        if fraction.len() > decimals as usize {
+            return Err(StdError::generic_err(format!(
+                "amount {} has more than {} decimal places",
+                amount, decimals
+            )));
+        }
+
+        let base_units = format!("{}{:0<width$}", whole, fraction, width = decimals as usize);
+        Ok(Self {
+            info,
+            amount: Uint128::from_str(&base_units)?,
         })
     }
 }
 
+impl fmt::Display for AssetUnchecked {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        write!(f, "{}:{}", self.info, self.amount)
+    }
+}
+
 impl fmt::Display for Asset {
     fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
         write!(f, "{}:{}", self.info, self.amount)
@@ -187,8 +264,20 @@ impl From<astroport::asset::Asset> for Asset {
 }
 
 #[cfg(feature = "astroport")]
-impl From<Asset> for astroport::asset::Asset {
-    fn from(asset: Asset) -> Self {
+impl TryFrom<Asset> for astroport::asset::Asset {
+    type Error = StdError;
+
+    fn try_from(asset: Asset) -> StdResult<Self> {
+        Ok(Self {
+            info: asset.info.try_into()?,
+            amount: asset.amount,
+        })
+    }
+}
+
+#[cfg(feature = "astroport")]
+impl From<astroport::asset::Asset> for AssetUnchecked {
+    fn from(asset: astroport::asset::Asset) -> Self {
         Self {
             info: asset.info.into(),
             amount: asset.amount,
@@ -196,6 +285,91 @@ impl From<Asset> for astroport::asset::Asset {
     }
 }
 
+#[cfg(feature = "terraswap")]
+impl From<terraswap::asset::Asset> for AssetUnchecked {
+    fn from(asset: terraswap::asset::Asset) -> Self {
+        Self {
+            info: asset.info.into(),
+            amount: asset.amount,
+        }
+    }
+}
+
+#[cfg(feature = "terraswap")]
+impl TryFrom<AssetUnchecked> for terraswap::asset::Asset {
+    type Error = StdError;
+
+    fn try_from(asset: AssetUnchecked) -> StdResult<Self> {
+        Ok(Self {
+            info: asset.info.try_into()?,
+            amount: asset.amount,
+        })
+    }
+}
+
+#[cfg(feature = "terraswap")]
+impl TryFrom<Asset> for terraswap::asset::Asset {
+    type Error = StdError;
+
+    fn try_from(asset: Asset) -> StdResult<Self> {
+        Ok(Self {
+            info: asset.info.try_into()?,
+            amount: asset.amount,
+        })
+    }
+}
+
+#[cfg(feature = "cw-asset-compat")]
+impl<T: Into<String>> TryFrom<cw_asset::AssetBase<T>> for AssetBase<T> {
+    type Error = AssetError;
+
+    fn try_from(asset: cw_asset::AssetBase<T>) -> Result<Self, Self::Error> {
+        Ok(Self {
+            info: asset.info.try_into()?,
+            amount: asset.amount,
+        })
+    }
+}
+
+#[cfg(feature = "cw-asset-compat")]
+impl<T> TryFrom<AssetBase<T>> for cw_asset::AssetBase<T> {
+    type Error = StdError;
+
+    fn try_from(asset: AssetBase<T>) -> StdResult<Self> {
+        Ok(Self {
+            info: asset.info.try_into()?,
+            amount: asset.amount,
+        })
+    }
+}
+
+#[cfg(feature = "osmosis")]
+impl TryFrom<osmosis_std::types::cosmos::base::v1beta1::Coin> for Asset {
+    type Error = StdError;
+
+    fn try_from(coin: osmosis_std::types::cosmos::base::v1beta1::Coin) -> StdResult<Self> {
+        Ok(Coin::try_from(coin)?.into())
+    }
+}
+
+#[cfg(feature = "osmosis")]
+impl TryFrom<Asset> for osmosis_std::types::cosmos::base::v1beta1::Coin {
+    type Error = StdError;
+
+    fn try_from(asset: Asset) -> StdResult<Self> {
+        Ok(Coin::try_from(asset)?.into())
+    }
+}
+
+#[cfg(feature = "osmosis")]
+impl TryFrom<&Asset> for osmosis_std::types::cosmos::base::v1beta1::Coin {
+    type Error = StdError;
+
+    fn try_from(asset: &Asset) -> StdResult<Self> {
+        asset.clone().try_into()
+    }
+}
+
 impl Asset {
     /// Create a new `AssetBase` instance based on given asset info and amount
     pub fn new<B: Into<Uint128>>(info: AssetInfo, amount: B) -> Self {
@@ -223,10 +397,31 @@ impl Asset {
         }
     }
 
-    /// Generate a message that sends a CW20 token to the specified recipient
-    /// with a binary payload
+    /// Create a new `AssetBase` instance representing the CW721 NFT of given
+    /// contract address and token id, with an amount of 1
+    pub fn cw721<A: Into<String>>(contract_addr: Addr, token_id: A) -> Self {
+        Self {
+            info: AssetInfo::cw721(contract_addr, token_id),
+            amount: Uint128::one(),
+        }
+    }
+
+    /// Return an error if the asset is a CW721 NFT with an amount other than 0
+    /// or 1
+    pub(crate) fn check_nft_amount(&self) -> StdResult<()> {
+        if self.info.is_nft() && self.amount > Uint128::one() {
+            return Err(StdError::generic_err(format!(
+                "cw721 asset amount must be 0 or 1: {}",
+                self
+            )));
+        }
+        Ok(())
+    }
+
+    /// Generate a message that sends a CW20 token or CW721 NFT to the
+    /// specified recipient with a binary payload
     ///
-    /// NOTE: Only works for CW20 tokens
+    /// NOTE: Only works for CW20 tokens and CW721 NFTs
     ///
     /// **Usage:**
     /// The following code generates a message that sends 12345 units of a mock
@@ -234,19 +429,37 @@ impl Asset {
     ///
     /// ```rust
     /// let asset = Asset::cw20(Addr::unchecked("mock_token"), 12345);
-    /// let msg = asset.send_msg("mock_contract", to_binary(&ExecuteMsg::MockFunction {})?)?;
+    /// let msg = asset.send_msg("mock_contract", to_json_binary(&ExecuteMsg::MockFunction {})?)?;
     /// ```
     pub fn send_msg<A: Into<String>>(&self, to: A, msg: Binary) -> StdResult<CosmosMsg> {
+        self.send_msg_custom(to, msg)
+    }
+
+    /// Like `send_msg`, but for a contract using custom messages
+    pub fn send_msg_custom<A: Into<String>, T>(
+        &self,
+        to: A,
+        msg: Binary,
+    ) -> StdResult<CosmosMsg<T>> {
         match &self.info {
             AssetInfo::Cw20(contract_addr) => Ok(CosmosMsg::Wasm(WasmMsg::Execute {
                 contract_addr: contract_addr.into(),
-                msg: to_binary(&Cw20ExecuteMsg::Send {
+                msg: to_json_binary(&Cw20ExecuteMsg::Send {
                     contract: to.into(),
                     amount: self.amount,
                     msg,
                 })?,
                 funds: vec![],
             })),
+            AssetInfo::Cw721(contract_addr, token_id) => Ok(CosmosMsg::Wasm(WasmMsg::Execute {
+                contract_addr: contract_addr.into(),
+                msg: to_json_binary(&Cw721ExecuteMsg::SendNft {
+                    contract: to.into(),
+                    token_id: self.nft_token_id_to_move(token_id)?,
+                    msg,
+                })?,
+                funds: vec![],
+            })),
             AssetInfo::Native(_) => Err(StdError::generic_err(
                 "native coins do not have `send` method",
             )),
@@ -269,10 +482,15 @@ impl Asset {
     /// let msg = asset.deduct_tax(&deps.querier)?.transfer_msg("alice")?;
     /// ```
     pub fn transfer_msg<A: Into<String>>(&self, to: A) -> StdResult<CosmosMsg> {
+        self.transfer_msg_custom(to)
+    }
+
+    /// Like `transfer_msg`, but for a contract using custom messages
+    pub fn transfer_msg_custom<A: Into<String>, T>(&self, to: A) -> StdResult<CosmosMsg<T>> {
         match &self.info {
             AssetInfo::Cw20(contract_addr) => Ok(CosmosMsg::Wasm(WasmMsg::Execute {
                 contract_addr: contract_addr.into(),
-                msg: to_binary(&Cw20ExecuteMsg::Transfer {
+                msg: to_json_binary(&Cw20ExecuteMsg::Transfer {
                     recipient: to.into(),
                     amount: self.amount,
                 })?,
@@ -285,6 +503,14 @@ impl Asset {
                     amount: self.amount,
                 }],
             })),
+            AssetInfo::Cw721(contract_addr, token_id) => Ok(CosmosMsg::Wasm(WasmMsg::Execute {
+                contract_addr: contract_addr.into(),
+                msg: to_json_binary(&Cw721ExecuteMsg::TransferNft {
+                    recipient: to.into(),
+                    token_id: self.nft_token_id_to_move(token_id)?,
+                })?,
+                funds: vec![],
+            })),
         }
     }
 
@@ -305,10 +531,19 @@ impl Asset {
         from: A,
         to: B,
     ) -> StdResult<CosmosMsg> {
+        self.transfer_from_msg_custom(from, to)
+    }
+
+    /// Like `transfer_from_msg`, but for a contract using custom messages
+    pub fn transfer_from_msg_custom<A: Into<String>, B: Into<String>, T>(
+        &self,
+        from: A,
+        to: B,
+    ) -> StdResult<CosmosMsg<T>> {
         match &self.info {
             AssetInfo::Cw20(contract_addr) => Ok(CosmosMsg::Wasm(WasmMsg::Execute {
                 contract_addr: contract_addr.into(),
-                msg: to_binary(&Cw20ExecuteMsg::TransferFrom {
+                msg: to_json_binary(&Cw20ExecuteMsg::TransferFrom {
                     owner: from.into(),
                     recipient: to.into(),
                     amount: self.amount,
@@ -318,31 +553,139 @@ impl Asset {
             AssetInfo::Native(_) => Err(StdError::generic_err(
                 "native coins do not have `transfer_from` method",
             )),
+            AssetInfo::Cw721(..) => Err(StdError::generic_err(
+                "cw721 tokens do not have `transfer_from` method",
+            )),
         }
     }
 
+    /// Return the token id of a CW721 NFT, if the asset's amount is exactly 1,
+    /// i.e. the NFT can be moved
+    fn nft_token_id_to_move(&self, token_id: &str) -> StdResult<String> {
+        if self.amount != Uint128::one() {
+            return Err(StdError::generic_err(format!(
+                "cw721 asset amount must be 1 to be moved: {}",
+                self
+            )));
+        }
+        Ok(token_id.to_string())
+    }
+
     /// Query balance of the asset for the given address
-    pub fn query_balance(&self, querier: &QuerierWrapper, addr: &Addr) -> StdResult<Uint128> {
-        match &self.info {
-            AssetInfo::Cw20(contract_addr) => {
-                let res: cw20::BalanceResponse = from_binary(&querier.query_wasm_smart(
-                    contract_addr.as_str(),
-                    &Cw20QueryMsg::Balance {
-                        address: addr.to_string(),
-                    },
-                )?)?;
-                Ok(res.balance)
-            }
-            AssetInfo::Native(denom) => querier
-                .query_balance(addr.as_str(), denom.as_str())
-                .map(|c| c.amount),
+    pub fn query_balance(&self, querier: &dyn AssetQuerier, addr: &Addr) -> StdResult<Uint128> {
+        self.info.query_balance(querier, addr)
+    }
+
+    /// Format the amount in whole units of an asset with the given number of
+    /// decimals, followed by the symbol of the whole unit, e.g. `0.069420 USD`
+    /// for 69420 uusd with 6 decimals and symbol `USD`
+    pub fn to_human(&self, decimals: u8, symbol: &str) -> String {
+        let decimals = decimals as usize;
+        let amount = format!("{:0>width$}", self.amount, width = decimals + 1);
+        let (whole, fraction) = amount.split_at(amount.len() - decimals);
+        if fraction.is_empty() {
+            format!("{} {}", whole, symbol)
+        } else {
+            format!("{}.{} {}", whole, fraction, symbol)
         }
     }
+
+    /// Format the amount like `to_human`, reading the asset's decimals and
+    /// symbol from the given provider
+    pub fn to_human_with(
+        &self,
+        querier: &dyn AssetQuerier,
+        provider: &dyn DecimalsProvider,
+    ) -> StdResult<String> {
+        Ok(self.to_human(
+            provider.decimals(querier, &self.info)?,
+            &provider.symbol(querier, &self.info)?,
+        ))
+    }
+
+    /// Return the value of the asset in base units of the price source's quote
+    /// asset, rounded down
+    pub fn value(
+        &self,
+        querier: &dyn AssetQuerier,
+        source: &dyn PriceSource,
+    ) -> StdResult<Uint128> {
+        source.value(querier, self)
+    }
+
+    /// Generate wasm event attributes describing the asset
+    ///
+    /// The attributes are `{prefix}_kind`, either `native`, `cw20` or `cw721`,
+    /// followed by `{prefix}_denom` or `{prefix}_contract` (plus
+    /// `{prefix}_token_id` for NFTs), and `{prefix}_amount`. The asset can be
+    /// read back with `Asset::from_attributes`.
+    ///
+    /// **Usage:**
+    /// The following code generates attributes `offer_kind=native`,
+    /// `offer_denom=uusd` and `offer_amount=12345`.
+    ///
+    /// ```rust
+    /// let asset = Asset::native("uusd", 12345u128);
+    /// let res = Response::new().add_attributes(asset.to_attributes("offer"));
+    /// ```
+    pub fn to_attributes(&self, prefix: &str) -> Vec<Attribute> {
+        let mut attributes = match &self.info {
+            AssetInfo::Native(denom) => vec![
+                Attribute::new(attribute_key(prefix, "kind"), "native"),
+                Attribute::new(attribute_key(prefix, "denom"), denom),
+            ],
+            AssetInfo::Cw20(contract_addr) => vec![
+                Attribute::new(attribute_key(prefix, "kind"), "cw20"),
+                Attribute::new(attribute_key(prefix, "contract"), contract_addr),
+            ],
+            AssetInfo::Cw721(contract_addr, token_id) => vec![
+                Attribute::new(attribute_key(prefix, "kind"), "cw721"),
+                Attribute::new(attribute_key(prefix, "contract"), contract_addr),
+                Attribute::new(attribute_key(prefix, "token_id"), token_id),
+            ],
+        };
+        attributes.push(Attribute::new(
+            attribute_key(prefix, "amount"),
+            self.amount.to_string(),
+        ));
+        attributes
+    }
+
+    /// Read an asset back from attributes generated by `Asset::to_attributes`
+    /// with the same prefix
+    pub fn from_attributes(prefix: &str, attributes: &[Attribute]) -> StdResult<Self> {
+        let kind = get_attribute(attributes, &attribute_key(prefix, "kind"))?;
+        let info = match kind {
+            "native" => {
+                AssetInfo::native(get_attribute(attributes, &attribute_key(prefix, "denom"))?)
+            }
+            "cw20" => AssetInfo::cw20(Addr::unchecked(get_attribute(
+                attributes,
+                &attribute_key(prefix, "contract"),
+            )?)),
+            "cw721" => AssetInfo::cw721(
+                Addr::unchecked(get_attribute(
+                    attributes,
+                    &attribute_key(prefix, "contract"),
+                )?),
+                get_attribute(attributes, &attribute_key(prefix, "token_id"))?,
+            ),
+            kind => {
+                return Err(StdError::generic_err(format!(
+                    "invalid asset kind: {}",
+                    kind
+                )))
+            }
+        };
+        let amount = get_attribute(attributes, &attribute_key(prefix, "amount"))?;
+        Ok(Asset::new(info, Uint128::try_from(amount)?))
+    }
 }
 
 #[cfg(test)]
 mod tests {
     use super::*;
+    use cosmwasm_std::attr;
     use cosmwasm_std::testing::MockApi;
 
     use test_case::test_case;
@@ -416,13 +759,66 @@ mod tests {
 
         let asset = Asset::cw20(Addr::unchecked("mock_token"), 88888u128);
         assert_eq!(asset.to_string(), String::from("mock_token:88888"));
+
+        let asset = AssetUnchecked::cw20("mock_token", 88888u128);
+        assert_eq!(asset.to_string(), String::from("mock_token:88888"));
+    }
+
+    #[test_case(69420, 6 => "0.069420 USD" ; "fraction")]
+    #[test_case(1_500_000, 6 => "1.500000 USD" ; "whole and fraction")]
+    #[test_case(69420, 0 => "69420 USD" ; "no decimals")]
+    #[test_case(0, 2 => "0.00 USD" ; "zero")]
+    fn to_human(amount: u128, decimals: u8) -> String {
+        Asset::native("uusd", amount).to_human(decimals, "USD")
+    }
+
+    #[test]
+    fn to_human_with() {
+        use crate::StaticDecimalsProvider;
+        use cosmwasm_std::testing::MockQuerier;
+        use cosmwasm_std::QuerierWrapper;
+
+        let querier = MockQuerier::default();
+        let querier: QuerierWrapper = QuerierWrapper::new(&querier);
+        let provider =
+            StaticDecimalsProvider::new(vec![(AssetInfo::native("uusd"), "USD".to_string(), 6)]);
+
+        let asset = Asset::native("uusd", 69420u128);
+        assert_eq!(
+            asset.to_human_with(&querier, &provider).unwrap(),
+            "0.069420 USD"
+        );
+        assert!(apollo().to_human_with(&querier, &provider).is_err());
+    }
+
+    #[test_case("1.5", 6 => Ok(Uint128::new(1_500_000)) ; "fraction")]
+    #[test_case("42", 6 => Ok(Uint128::new(42_000_000)) ; "whole")]
+    #[test_case("0.000001", 6 => Ok(Uint128::new(1)) ; "smallest unit")]
+    #[test_case("42", 0 => Ok(Uint128::new(42)) ; "no decimals")]
+    #[test_case("0.0000001", 6 => Err(StdError::generic_err(
+        "amount 0.0000001 has more than 6 decimal places")) ; "excess precision")]
+    #[test_case("1.5", 0 => matches Err(_) ; "excess precision no decimals")]
+    #[test_case("", 6 => matches Err(_) ; "empty")]
+    #[test_case(".5", 6 => matches Err(_) ; "missing whole")]
+    #[test_case("1.", 6 => matches Err(_) ; "missing fraction")]
+    #[test_case("-1", 6 => matches Err(_) ; "negative")]
+    #[test_case("1e6", 6 => matches Err(_) ; "exponent")]
+    #[test_case("1.2.3", 6 => matches Err(_) ; "two points")]
+    #[test_case("340282366920938463463374607431768211456", 0 => matches Err(_) ; "overflow")]
+    fn from_human(amount: &str, decimals: u8) -> StdResult<Uint128> {
+        AssetUnchecked::from_human(AssetInfoUnchecked::native("uusd"), amount, decimals)
+            .map(|asset| asset.amount)
     }
 
     #[test]
     fn casting() {
         let api = MockApi::default();
 
+        // `MockApi` only accepts bech32 addresses in cosmwasm-std 2.x
+        #[cfg(feature = "cosmwasm-1")]
         let checked = Asset::cw20(Addr::unchecked("mock_token"), 123456u128);
+        #[cfg(not(feature = "cosmwasm-1"))]
+        let checked = Asset::cw20(api.addr_make("mock_token"), 123456u128);
         let unchecked: AssetUnchecked = checked.clone().into();
 
         assert_eq!(unchecked.check(&api).unwrap(), checked);
@@ -433,16 +829,16 @@ mod tests {
         let token = Asset::cw20(Addr::unchecked("mock_token"), 123456u128);
         let coin = Asset::native("uusd", 123456u128);
 
-        let bin_msg = to_binary(&MockExecuteMsg::MockCommand {}).unwrap();
+        let bin_msg = to_json_binary(&MockExecuteMsg::MockCommand {}).unwrap();
         let msg = token.send_msg("mock_contract", bin_msg.clone()).unwrap();
         assert_eq!(
             msg,
             CosmosMsg::Wasm(WasmMsg::Execute {
                 contract_addr: String::from("mock_token"),
-                msg: to_binary(&Cw20ExecuteMsg::Send {
+                msg: to_json_binary(&Cw20ExecuteMsg::Send {
                     contract: String::from("mock_contract"),
                     amount: Uint128::new(123456),
-                    msg: to_binary(&MockExecuteMsg::MockCommand {}).unwrap()
+                    msg: to_json_binary(&MockExecuteMsg::MockCommand {}).unwrap()
                 })
                 .unwrap(),
                 funds: vec![]
@@ -462,7 +858,7 @@ mod tests {
             msg,
             CosmosMsg::Wasm(WasmMsg::Execute {
                 contract_addr: String::from("mock_token"),
-                msg: to_binary(&Cw20ExecuteMsg::Transfer {
+                msg: to_json_binary(&Cw20ExecuteMsg::Transfer {
                     recipient: String::from("alice"),
                     amount: Uint128::new(123456)
                 })
@@ -476,7 +872,7 @@ mod tests {
             msg,
             CosmosMsg::Bank(BankMsg::Send {
                 to_address: String::from("alice"),
-                amount: vec![Coin::new(123456, "uusd")]
+                amount: vec![Coin::new(123456u128, "uusd")]
             })
         );
 
@@ -485,7 +881,7 @@ mod tests {
             msg,
             CosmosMsg::Wasm(WasmMsg::Execute {
                 contract_addr: String::from("mock_token"),
-                msg: to_binary(&Cw20ExecuteMsg::TransferFrom {
+                msg: to_json_binary(&Cw20ExecuteMsg::TransferFrom {
                     owner: String::from("bob"),
                     recipient: String::from("charlie"),
                     amount: Uint128::new(123456)
@@ -504,6 +900,78 @@ mod tests {
         );
     }
 
+    #[test]
+    fn creating_nft_messages() {
+        let nft = Asset::cw721(Addr::unchecked("mock_nft"), "42");
+
+        let msg: CosmosMsg = nft.transfer_msg("alice").unwrap();
+        assert_eq!(
+            msg,
+            CosmosMsg::Wasm(WasmMsg::Execute {
+                contract_addr: String::from("mock_nft"),
+                msg: Binary::from(
+                    br#"{"transfer_nft":{"recipient":"alice","token_id":"42"}}"#.as_slice()
+                ),
+                funds: vec![]
+            })
+        );
+
+        let bin_msg = to_json_binary(&MockExecuteMsg::MockCommand {}).unwrap();
+        let msg: CosmosMsg = nft.send_msg("mock_contract", bin_msg.clone()).unwrap();
+        assert_eq!(
+            msg,
+            CosmosMsg::Wasm(WasmMsg::Execute {
+                contract_addr: String::from("mock_nft"),
+                msg: Binary::from(
+                    format!(
+                        r#"{{"send_nft":{{"contract":"mock_contract","token_id":"42","msg":"{}"}}}}"#,
+                        bin_msg.to_base64()
+                    )
+                    .as_bytes()
+                ),
+                funds: vec![]
+            })
+        );
+
+        let err: StdResult<CosmosMsg> = nft.transfer_from_msg("bob", "charlie");
+        assert_eq!(
+            err,
+            Err(StdError::generic_err(
+                "cw721 tokens do not have `transfer_from` method"
+            ))
+        );
+
+        let nothing = Asset::new(nft.info.clone(), 0u128);
+        let err: StdResult<CosmosMsg> = nothing.transfer_msg("alice");
+        assert_eq!(
+            err,
+            Err(StdError::generic_err(
+                "cw721 asset amount must be 1 to be moved: mock_nft:42:0"
+            ))
+        );
+    }
+
+    #[test]
+    fn checking_nft_amounts() {
+        let api = MockApi::default();
+        let contract_addr = api.addr_make("mock_nft");
+
+        let unchecked = AssetUnchecked::cw721(contract_addr.as_str(), "42");
+        assert_eq!(
+            unchecked.check(&api).unwrap(),
+            Asset::cw721(contract_addr.clone(), "42")
+        );
+
+        let unchecked = AssetUnchecked::new(unchecked.info, 2u128);
+        assert_eq!(
+            unchecked.check(&api),
+            Err(StdError::generic_err(format!(
+                "cw721 asset amount must be 0 or 1: {}:42:2",
+                contract_addr
+            )))
+        );
+    }
+
     #[test]
     fn new() {
         let asset = Asset::new(AssetInfo::Native(String::from("uusd")), 123456u128);
@@ -598,6 +1066,52 @@ mod tests {
         assert_eq!(apollo(), Asset::from(coin));
     }
 
+    #[test]
+    #[cfg(feature = "terraswap")]
+    fn terraswap_asset_conversions() {
+        let ts_asset = terraswap::asset::Asset {
+            info: terraswap::asset::AssetInfo::Token {
+                contract_addr: "apollo".to_string(),
+            },
+            amount: Uint128::new(123456u128),
+        };
+
+        let unchecked = AssetUnchecked::from(ts_asset.clone());
+        assert_eq!(unchecked, apollo().into());
+        assert_eq!(unchecked.check(&MockApi::default()).unwrap(), apollo());
+
+        assert_eq!(
+            terraswap::asset::Asset::try_from(apollo()).unwrap(),
+            ts_asset
+        );
+        assert_eq!(
+            terraswap::asset::Asset::try_from(unchecked).unwrap(),
+            ts_asset
+        );
+    }
+
+    #[test]
+    #[cfg(feature = "cw-asset-compat")]
+    fn cw_asset_asset_conversions() {
+        use cosmwasm_std::{from_json, to_json_vec};
+
+        for asset in [apollo(), uusd()] {
+            let upstream: cw_asset::Asset = asset.clone().try_into().unwrap();
+            assert_eq!(Asset::try_from(upstream.clone()).unwrap(), asset);
+
+            let json = to_json_vec(&asset).unwrap();
+            assert_eq!(json, to_json_vec(&upstream).unwrap());
+            assert_eq!(from_json::<cw_asset::Asset>(&json).unwrap(), upstream);
+
+            let unchecked: AssetUnchecked = asset.into();
+            let upstream: cw_asset::AssetUnchecked = unchecked.clone().try_into().unwrap();
+            assert_eq!(AssetUnchecked::try_from(upstream).unwrap(), unchecked);
+        }
+
+        let upstream = cw_asset::Asset::cw1155(Addr::unchecked("mock_nft"), "1", 1u128);
+        assert!(Asset::try_from(upstream).is_err());
+    }
+
     #[test_case(uusd() => matches Err(_) ; "native")]
     #[test_case(apollo() => Ok(Cw20CoinVerified {
                     address: Addr::unchecked("apollo"),
@@ -624,4 +1138,50 @@ mod tests {
     fn try_from_assetunchecked_for_cw20coin(asset: AssetUnchecked) -> StdResult<Cw20Coin> {
         Cw20Coin::try_from(asset)
     }
+
+    #[test]
+    fn asset_attributes() {
+        let asset = Asset::native("uusd", 69420u128);
+        let attributes = asset.to_attributes("offer");
+        assert_eq!(
+            attributes,
+            vec![
+                attr("offer_kind", "native"),
+                attr("offer_denom", "uusd"),
+                attr("offer_amount", "69420"),
+            ]
+        );
+        assert_eq!(Asset::from_attributes("offer", &attributes).unwrap(), asset);
+
+        let asset = Asset::cw20(Addr::unchecked("mock_token"), 88888u128);
+        let attributes = asset.to_attributes("");
+        assert_eq!(
+            attributes,
+            vec![
+                attr("kind", "cw20"),
+                attr("contract", "mock_token"),
+                attr("amount", "88888"),
+            ]
+        );
+        assert_eq!(Asset::from_attributes("", &attributes).unwrap(), asset);
+
+        let asset = Asset::cw721(Addr::unchecked("mock_nft"), "42");
+        let attributes = asset.to_attributes("nft");
+        assert_eq!(
+            attributes,
+            vec![
+                attr("nft_kind", "cw721"),
+                attr("nft_contract", "mock_nft"),
+                attr("nft_token_id", "42"),
+                attr("nft_amount", "1"),
+            ]
+        );
+        assert_eq!(Asset::from_attributes("nft", &attributes).unwrap(), asset);
+
+        let attributes = vec![attr("kind", "cw1155"), attr("amount", "1")];
+        assert_eq!(
+            Asset::from_attributes("", &attributes),
+            Err(StdError::generic_err("invalid asset kind: cw1155"))
+        );
+    }
 }
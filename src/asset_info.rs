@@ -2,23 +2,21 @@ use std::convert::TryFrom;
 use std::fmt;
 use std::fmt::Formatter;
 
-use cosmwasm_std::{
-    to_binary, Addr, Api, BalanceResponse, BankQuery, QuerierWrapper, QueryRequest, StdError,
-    StdResult, Uint128, WasmQuery,
-};
-use cw20::{BalanceResponse as Cw20BalanceResponse, Cw20QueryMsg, Denom};
+use cosmwasm_std::{Addr, Api, StdError, StdResult, Uint128};
+use cw20::Denom;
 
 use cw_storage_plus::{Key, KeyDeserialize, Prefixer, PrimaryKey};
 use schemars::JsonSchema;
 use serde::{Deserialize, Serialize};
 
-use crate::Asset;
+use crate::{Asset, AssetError, AssetQuerier};
 
 #[derive(Serialize, Deserialize, Clone, Debug, Eq, PartialEq, Hash, JsonSchema)]
 #[serde(rename_all = "snake_case")]
 pub enum AssetInfoBase<T> {
-    Cw20(T),        // the contract address, String or cosmwasm_std::Addr
-    Native(String), // the native token's denom
+    Cw20(T),          // the contract address, String or cosmwasm_std::Addr
+    Native(String),   // the native token's denom
+    Cw721(T, String), // the NFT contract address and the token id
 }
 
 pub type AssetInfoUnchecked = AssetInfoBase<String>;
@@ -29,6 +27,9 @@ impl From<AssetInfo> for AssetInfoUnchecked {
         match &asset_info {
             AssetInfo::Cw20(contract_addr) => AssetInfoUnchecked::Cw20(contract_addr.into()),
             AssetInfo::Native(denom) => AssetInfoUnchecked::Native(denom.clone()),
+            AssetInfo::Cw721(contract_addr, token_id) => {
+                AssetInfoUnchecked::Cw721(contract_addr.into(), token_id.clone())
+            }
         }
     }
 }
@@ -48,11 +49,16 @@ impl From<Denom> for AssetInfo {
     }
 }
 
-impl From<AssetInfo> for Denom {
-    fn from(asset_info: AssetInfo) -> Self {
+impl TryFrom<AssetInfo> for Denom {
+    type Error = StdError;
+
+    fn try_from(asset_info: AssetInfo) -> StdResult<Self> {
         match asset_info {
-            AssetInfo::Cw20(contract_addr) => Denom::Cw20(contract_addr),
-            AssetInfo::Native(denom) => Denom::Native(denom),
+            AssetInfo::Cw20(contract_addr) => Ok(Denom::Cw20(contract_addr)),
+            AssetInfo::Native(denom) => Ok(Denom::Native(denom)),
+            AssetInfo::Cw721(..) => Err(StdError::generic_err(
+                "Cannot convert CW721 asset info to Denom",
+            )),
         }
     }
 }
@@ -68,29 +74,238 @@ impl From<astroport::asset::AssetInfo> for AssetInfo {
 }
 
 #[cfg(feature = "astroport")]
-impl From<AssetInfo> for astroport::asset::AssetInfo {
-    fn from(value: AssetInfo) -> Self {
+impl TryFrom<AssetInfo> for astroport::asset::AssetInfo {
+    type Error = StdError;
+
+    fn try_from(value: AssetInfo) -> StdResult<Self> {
         match value {
-            AssetInfoBase::Cw20(addr) => astroport::asset::AssetInfo::Token {
+            AssetInfoBase::Cw20(addr) => Ok(astroport::asset::AssetInfo::Token {
                 contract_addr: addr,
-            },
-            AssetInfoBase::Native(denom) => astroport::asset::AssetInfo::NativeToken { denom },
+            }),
+            AssetInfoBase::Native(denom) => Ok(astroport::asset::AssetInfo::NativeToken { denom }),
+            AssetInfoBase::Cw721(..) => Err(StdError::generic_err(
+                "Cannot convert CW721 asset info to Astroport asset info",
+            )),
+        }
+    }
+}
+
+#[cfg(feature = "astroport")]
+impl From<astroport::asset::AssetInfo> for AssetInfoUnchecked {
+    fn from(value: astroport::asset::AssetInfo) -> Self {
+        AssetInfo::from(value).into()
+    }
+}
+
+#[cfg(feature = "terraswap")]
+impl From<terraswap::asset::AssetInfo> for AssetInfoUnchecked {
+    fn from(value: terraswap::asset::AssetInfo) -> Self {
+        match value {
+            terraswap::asset::AssetInfo::Token { contract_addr } => {
+                AssetInfoUnchecked::Cw20(contract_addr)
+            }
+            terraswap::asset::AssetInfo::NativeToken { denom } => AssetInfoUnchecked::Native(denom),
+        }
+    }
+}
+
+#[cfg(feature = "terraswap")]
+impl TryFrom<AssetInfoUnchecked> for terraswap::asset::AssetInfo {
+    type Error = StdError;
+
+    fn try_from(value: AssetInfoUnchecked) -> StdResult<Self> {
+        match value {
+            AssetInfoBase::Cw20(contract_addr) => {
+                Ok(terraswap::asset::AssetInfo::Token { contract_addr })
+            }
+            AssetInfoBase::Native(denom) => Ok(terraswap::asset::AssetInfo::NativeToken { denom }),
+            AssetInfoBase::Cw721(..) => Err(StdError::generic_err(
+                "Cannot convert CW721 asset info to Terraswap asset info",
+            )),
+        }
+    }
+}
+
+#[cfg(feature = "terraswap")]
+impl TryFrom<AssetInfo> for terraswap::asset::AssetInfo {
+    type Error = StdError;
+
+    fn try_from(value: AssetInfo) -> StdResult<Self> {
+        AssetInfoUnchecked::from(value).try_into()
+    }
+}
+
+#[cfg(feature = "cw-asset-compat")]
+impl<T: Into<String>> TryFrom<cw_asset::AssetInfoBase<T>> for AssetInfoBase<T> {
+    type Error = AssetError;
+
+    fn try_from(value: cw_asset::AssetInfoBase<T>) -> Result<Self, Self::Error> {
+        match value {
+            cw_asset::AssetInfoBase::Cw20(contract_addr) => Ok(AssetInfoBase::Cw20(contract_addr)),
+            cw_asset::AssetInfoBase::Native(denom) => Ok(AssetInfoBase::Native(denom)),
+            cw_asset::AssetInfoBase::Cw1155(contract_addr, token_id) => {
+                Err(AssetError::UnsupportedCw1155 {
+                    contract_addr: contract_addr.into(),
+                    token_id,
+                })
+            }
+            _ => Err(AssetError::Std(StdError::generic_err(
+                "unsupported cw-asset asset info variant",
+            ))),
+        }
+    }
+}
+
+#[cfg(feature = "cw-asset-compat")]
+impl<T> TryFrom<AssetInfoBase<T>> for cw_asset::AssetInfoBase<T> {
+    type Error = StdError;
+
+    fn try_from(value: AssetInfoBase<T>) -> StdResult<Self> {
+        match value {
+            AssetInfoBase::Cw20(contract_addr) => Ok(cw_asset::AssetInfoBase::Cw20(contract_addr)),
+            AssetInfoBase::Native(denom) => Ok(cw_asset::AssetInfoBase::Native(denom)),
+            AssetInfoBase::Cw721(..) => Err(StdError::generic_err(
+                "Cannot convert CW721 asset info to cw-asset asset info",
+            )),
         }
     }
 }
 
+/// Options for `AssetInfoUnchecked::check_with_options`
+#[derive(Clone, Copy, Debug, Default, PartialEq, Eq)]
+pub struct CheckOptions {
+    /// Require native coins to have a nonzero bank supply, i.e. to exist
+    ///
+    /// NOTE: Requires the `native-supply` feature, otherwise checking a native
+    /// coin with this option fails.
+    pub require_native_supply: bool,
+}
+
+/// Validate a native coin denom against the rules of the Cosmos SDK
+///
+/// A denom is 3 to 128 characters long, starts with a letter, and otherwise
+/// consists of letters, digits and the characters `/:._-`. Additionally, IBC
+/// denoms must be of the form `ibc/{hash}` with a 64 character hex hash, and
+/// token factory denoms of the form `factory/{creator}/{subdenom}`.
+pub fn validate_native_denom(denom: &str) -> StdResult<()> {
+    let invalid =
+        |reason: &str| StdError::generic_err(format!("invalid denom {}: {}", denom, reason));
+
+    if denom.len() < 3 || denom.len() > 128 {
+        return Err(invalid("must be between 3 and 128 characters"));
+    }
+    if !denom.starts_with(|c: char| c.is_ascii_alphabetic()) {
+        return Err(invalid("must start with a letter"));
+    }
+    if !denom
+        .chars()
+        .all(|c| c.is_ascii_alphanumeric() || "/:._-".contains(c))
+    {
+        return Err(invalid("contains invalid characters"));
+    }
+    if let Some(hash) = denom.strip_prefix("ibc/") {
+        if hash.len() != 64 || !hash.chars().all(|c| c.is_ascii_hexdigit()) {
+            return Err(invalid("ibc hash must be 64 hex characters"));
+        }
+    }
+    if let Some(rest) = denom.strip_prefix("factory/") {
+        match rest.split_once('/') {
+            Some((creator, subdenom)) if !creator.is_empty() && !subdenom.is_empty() => {}
+            _ => return Err(invalid("must be of the form factory/{creator}/{subdenom}")),
+        }
+    }
+    Ok(())
+}
+
 impl AssetInfoUnchecked {
-    /// Validate contract address (if any) and returns a new `AssetInfo`
+    /// Validate contract address or denom and returns a new `AssetInfo`
     /// instance
     pub fn check(&self, api: &dyn Api) -> StdResult<AssetInfo> {
         Ok(match self {
             AssetInfoUnchecked::Cw20(contract_addr) => {
                 AssetInfo::Cw20(api.addr_validate(contract_addr)?)
             }
-            AssetInfoUnchecked::Native(denom) => AssetInfo::Native(denom.clone()),
+            AssetInfoUnchecked::Native(denom) => {
+                validate_native_denom(denom)?;
+                AssetInfo::Native(denom.clone())
+            }
+            AssetInfoUnchecked::Cw721(contract_addr, token_id) => {
+                if token_id.is_empty() {
+                    return Err(StdError::generic_err("cw721 token id must not be empty"));
+                }
+                AssetInfo::Cw721(api.addr_validate(contract_addr)?, token_id.clone())
+            }
         })
     }
 
+    /// Validate like `check`, and additionally run the on-chain checks enabled
+    /// in the options
+    pub fn check_with_options(
+        &self,
+        api: &dyn Api,
+        querier: &dyn AssetQuerier,
+        options: &CheckOptions,
+    ) -> StdResult<AssetInfo> {
+        let info = self.check(api)?;
+        if let AssetInfo::Native(denom) = &info {
+            if options.require_native_supply && querier.query_asset_supply(&info)?.is_zero() {
+                return Err(StdError::generic_err(format!(
+                    "denom has no supply: {}",
+                    denom
+                )));
+            }
+        }
+        Ok(info)
+    }
+
+    /// Validate like `check`, and additionally verify that a CW20 token's
+    /// address belongs to a contract which answers the CW20 `TokenInfo` query,
+    /// and that a CW721 token's address belongs to a contract
+    ///
+    /// Failures of the contract info query other than a missing contract are
+    /// returned as they are, rather than as `AssetError::NotAContract`.
+    pub fn check_with_querier(
+        &self,
+        api: &dyn Api,
+        querier: &dyn AssetQuerier,
+    ) -> Result<AssetInfo, AssetError> {
+        let info = self.check(api)?;
+        let contract_addr = match &info {
+            AssetInfo::Cw20(contract_addr) | AssetInfo::Cw721(contract_addr, _) => contract_addr,
+            AssetInfo::Native(_) => return Ok(info),
+        };
+        if !querier.query_is_contract(contract_addr.as_str())? {
+            return Err(AssetError::NotAContract {
+                address: contract_addr.to_string(),
+            });
+        }
+        if info.is_cw20() {
+            querier
+                .query_asset_metadata(&info)
+                .map_err(|error| AssetError::NotACw20 {
+                    address: contract_addr.to_string(),
+                    error,
+                })?;
+        }
+        Ok(info)
+    }
+
+    /// Re-encode a CW20 or CW721 token's bech32 address with the given prefix,
+    /// e.g. to reuse a config on another chain. Native coins are returned
+    /// unchanged.
+    pub fn with_bech32_prefix(&self, prefix: &str) -> Result<Self, AssetError> {
+        match self {
+            AssetInfoUnchecked::Cw20(contract_addr) => Ok(AssetInfoUnchecked::Cw20(
+                reencode_bech32(contract_addr, prefix)?,
+            )),
+            AssetInfoUnchecked::Cw721(contract_addr, token_id) => Ok(AssetInfoUnchecked::Cw721(
+                reencode_bech32(contract_addr, prefix)?,
+                token_id.clone(),
+            )),
+            AssetInfoUnchecked::Native(_) => Ok(self.clone()),
+        }
+    }
+
     pub fn native<A: Into<String>>(denom: A) -> Self {
         AssetInfoUnchecked::Native(denom.into())
     }
@@ -98,9 +313,24 @@ impl AssetInfoUnchecked {
     pub fn cw20<A: Into<String>>(contract_addr: A) -> Self {
         AssetInfoUnchecked::Cw20(contract_addr.into())
     }
+
+    pub fn cw721<A: Into<String>, B: Into<String>>(contract_addr: A, token_id: B) -> Self {
+        AssetInfoUnchecked::Cw721(contract_addr.into(), token_id.into())
+    }
+}
+
+fn reencode_bech32(address: &str, prefix: &str) -> Result<String, AssetError> {
+    let (_, data, variant) =
+        bech32::decode(address).map_err(|err| AssetError::InvalidBech32Address {
+            address: address.to_string(),
+            reason: err.to_string(),
+        })?;
+    bech32::encode(prefix, data, variant).map_err(|_| AssetError::InvalidBech32Prefix {
+        prefix: prefix.to_string(),
+    })
 }
 
-#[derive(Serialize, Deserialize, Clone, Debug, PartialEq, Eq, JsonSchema)]
+#[derive(Serialize, Deserialize, Clone, Debug, PartialEq, Eq, Hash, JsonSchema)]
 pub struct AssetInfoKey {
     bytes: Vec<u8>,
 }
@@ -109,6 +339,35 @@ impl AssetInfoKey {
     pub fn as_bytes(&self) -> &[u8] {
         &self.bytes
     }
+
+    /// Decode the key into the asset info it was created from, returning an
+    /// error if the bytes are not a valid key
+    pub fn to_asset_info(&self) -> StdResult<AssetInfo> {
+        let invalid = || StdError::generic_err(format!("invalid AssetInfoKey: {:?}", self.bytes));
+        let utf8 = |bytes: &[u8]| String::from_utf8(bytes.to_vec()).map_err(|_| invalid());
+
+        let (first_byte, rest) = self.bytes.split_first().ok_or_else(invalid)?;
+        match *first_byte {
+            u8::MIN => Ok(AssetInfo::Cw20(Addr::unchecked(utf8(rest)?))),
+            u8::MAX => Ok(AssetInfo::Native(utf8(rest)?)),
+            1u8 => {
+                if rest.len() < 2 {
+                    return Err(invalid());
+                }
+                let (len, rest) = rest.split_at(2);
+                let len = u16::from_be_bytes([len[0], len[1]]) as usize;
+                if rest.len() < len {
+                    return Err(invalid());
+                }
+                let (contract_addr, token_id) = rest.split_at(len);
+                Ok(AssetInfo::Cw721(
+                    Addr::unchecked(utf8(contract_addr)?),
+                    utf8(token_id)?,
+                ))
+            }
+            _ => Err(invalid()),
+        }
+    }
 }
 
 impl From<AssetInfo> for AssetInfoKey {
@@ -123,6 +382,14 @@ impl From<AssetInfo> for AssetInfoKey {
                 bytes.push(u8::MAX);
                 bytes.append(&mut denom.as_bytes().to_vec());
             }
+            AssetInfo::Cw721(contract_addr, token_id) => {
+                // the contract address is length-prefixed, so that the token id
+                // may contain arbitrary characters
+                bytes.push(1u8);
+                bytes.extend_from_slice(&(contract_addr.as_bytes().len() as u16).to_be_bytes());
+                bytes.append(&mut contract_addr.as_bytes().to_vec());
+                bytes.append(&mut token_id.as_bytes().to_vec());
+            }
         }
         AssetInfoKey { bytes }
     }
@@ -136,14 +403,10 @@ impl From<&AssetInfo> for AssetInfoKey {
 
 impl From<AssetInfoKey> for AssetInfo {
     fn from(asset_info_key: AssetInfoKey) -> Self {
-        let bytes = asset_info_key.bytes;
-        let first_byte = bytes[0];
-        let rest = String::from_utf8(bytes[1..].to_vec()).unwrap();
-        match first_byte {
-            u8::MIN => AssetInfo::Cw20(Addr::unchecked(rest)),
-            u8::MAX => AssetInfo::Native(rest),
-            _ => panic!("Invalid AssetInfoKey"),
-        }
+        // keys are validated when loaded from storage, see `KeyDeserialize`
+        asset_info_key
+            .to_asset_info()
+            .expect("Invalid AssetInfoKey")
     }
 }
 
@@ -153,7 +416,9 @@ impl TryFrom<AssetInfo> for Addr {
     fn try_from(asset_info: AssetInfo) -> StdResult<Self> {
         match asset_info {
             AssetInfo::Cw20(contract_addr) => Ok(contract_addr),
-            AssetInfo::Native(_) => Err(StdError::generic_err("Not a CW20 token")),
+            AssetInfo::Native(_) | AssetInfo::Cw721(..) => {
+                Err(StdError::generic_err("Not a CW20 token"))
+            }
         }
     }
 }
@@ -175,6 +440,9 @@ impl fmt::Display for AssetInfoUnchecked {
         match self {
             AssetInfoUnchecked::Cw20(contract_addr) => write!(f, "{}", contract_addr),
             AssetInfoUnchecked::Native(denom) => write!(f, "{}", denom),
+            AssetInfoUnchecked::Cw721(contract_addr, token_id) => {
+                write!(f, "{}:{}", contract_addr, token_id)
+            }
         }
     }
 }
@@ -184,6 +452,9 @@ impl fmt::Display for AssetInfo {
         match self {
             AssetInfo::Cw20(contract_addr) => write!(f, "{}", contract_addr),
             AssetInfo::Native(denom) => write!(f, "{}", denom),
+            AssetInfo::Cw721(contract_addr, token_id) => {
+                write!(f, "{}:{}", contract_addr, token_id)
+            }
         }
     }
 }
@@ -194,7 +465,7 @@ impl<'a> PrimaryKey<'a> for AssetInfoKey {
     type Suffix = Self;
     type SuperSuffix = Self;
 
-    fn key(&self) -> Vec<cw_storage_plus::Key> {
+    fn key(&self) -> Vec<cw_storage_plus::Key<'_>> {
         vec![Key::Ref(&self.bytes)]
     }
 }
@@ -202,13 +473,18 @@ impl<'a> PrimaryKey<'a> for AssetInfoKey {
 impl KeyDeserialize for AssetInfoKey {
     type Output = Self;
 
+    #[cfg(not(feature = "cosmwasm-1"))]
+    const KEY_ELEMS: u16 = 1;
+
     fn from_vec(value: Vec<u8>) -> StdResult<Self::Output> {
-        Ok(Self { bytes: value })
+        let key = Self { bytes: value };
+        key.to_asset_info()?;
+        Ok(key)
     }
 }
 
 impl<'a> Prefixer<'a> for AssetInfoKey {
-    fn prefix(&self) -> Vec<cw_storage_plus::Key> {
+    fn prefix(&self) -> Vec<cw_storage_plus::Key<'_>> {
         vec![Key::Ref(&self.bytes)]
     }
 }
@@ -226,6 +502,12 @@ impl AssetInfo {
         AssetInfo::Native(denom.into())
     }
 
+    /// Create a new `AssetInfoBase` instance representing the CW721 NFT of
+    /// given contract address and token id
+    pub fn cw721<A: Into<Addr>, B: Into<String>>(contract_addr: A, token_id: B) -> Self {
+        AssetInfo::Cw721(contract_addr.into(), token_id.into())
+    }
+
     pub fn from_str(api: &dyn Api, s: &str) -> Self {
         match api.addr_validate(s) {
             Ok(contract_addr) => AssetInfo::cw20(contract_addr),
@@ -236,35 +518,26 @@ impl AssetInfo {
     /// Query an address' balance of the asset
     pub fn query_balance<T: Into<String>>(
         &self,
-        querier: &QuerierWrapper,
+        querier: &dyn AssetQuerier,
         address: T,
     ) -> StdResult<Uint128> {
-        match self {
-            AssetInfo::Cw20(contract_addr) => {
-                let response: Cw20BalanceResponse =
-                    querier.query(&QueryRequest::Wasm(WasmQuery::Smart {
-                        contract_addr: contract_addr.into(),
-                        msg: to_binary(&Cw20QueryMsg::Balance {
-                            address: address.into(),
-                        })?,
-                    }))?;
-                Ok(response.balance)
-            }
-            AssetInfo::Native(denom) => {
-                let response: BalanceResponse =
-                    querier.query(&QueryRequest::Bank(BankQuery::Balance {
-                        address: address.into(),
-                        denom: denom.clone(),
-                    }))?;
-                Ok(response.amount.amount)
-            }
-        }
+        querier.query_asset_balance(self, &address.into())
     }
 
     pub fn is_native(&self) -> bool {
         matches!(self, AssetInfo::Native(_))
     }
 
+    pub fn is_cw20(&self) -> bool {
+        matches!(self, AssetInfo::Cw20(_))
+    }
+
+    /// Whether the asset is a CW721 NFT, of which an address owns an amount of
+    /// either 0 or 1
+    pub fn is_nft(&self) -> bool {
+        matches!(self, AssetInfo::Cw721(..))
+    }
+
     /// Create a new asset from the `AssetInfo` with the given amount
     pub fn to_asset(&self, amount: impl Into<Uint128>) -> Asset {
         Asset {
@@ -279,7 +552,14 @@ mod test {
     use std::convert::TryInto;
 
     use super::*;
-    use cosmwasm_std::testing::MockApi;
+    use crate::testing::{contract_info_result, no_such_contract_result};
+    use cosmwasm_std::testing::{MockApi, MockQuerier};
+    use cosmwasm_std::{
+        to_json_binary, Coin, ContractResult, QuerierWrapper, SystemResult, WasmQuery,
+    };
+    use cw20::TokenInfoResponse;
+
+    use test_case::test_case;
 
     #[test]
     fn creating_instances() {
@@ -311,16 +591,238 @@ mod test {
 
         let info = AssetInfo::cw20(Addr::unchecked("mock_token"));
         assert_eq!(info.to_string(), String::from("mock_token"));
+
+        let info = AssetInfo::cw721(Addr::unchecked("mock_nft"), "42");
+        assert_eq!(info.to_string(), String::from("mock_nft:42"));
     }
 
     #[test]
     fn checking() {
         let api = MockApi::default();
 
+        // `MockApi` only accepts bech32 addresses in cosmwasm-std 2.x
+        #[cfg(feature = "cosmwasm-1")]
         let checked = AssetInfo::cw20(Addr::unchecked("mock_token"));
+        #[cfg(not(feature = "cosmwasm-1"))]
+        let checked = AssetInfo::cw20(api.addr_make("mock_token"));
         let unchecked: AssetInfoUnchecked = checked.clone().into();
 
         assert_eq!(unchecked.check(&api).unwrap(), checked);
+
+        let checked = AssetInfo::cw721(api.addr_make("mock_nft"), "42");
+        let unchecked: AssetInfoUnchecked = checked.clone().into();
+        assert_eq!(unchecked.check(&api).unwrap(), checked);
+
+        let unchecked = AssetInfoUnchecked::cw721(api.addr_make("mock_nft"), "");
+        assert_eq!(
+            unchecked.check(&api),
+            Err(StdError::generic_err("cw721 token id must not be empty"))
+        );
+        assert!(AssetInfoUnchecked::cw721("Invalid", "42")
+            .check(&api)
+            .is_err());
+    }
+
+    #[test_case(AssetInfo::native("uusd") ; "native")]
+    #[test_case(AssetInfo::cw20(Addr::unchecked("mock_token")) ; "cw20")]
+    #[test_case(AssetInfo::cw721(Addr::unchecked("mock_nft"), "42") ; "cw721")]
+    #[test_case(AssetInfo::cw721(Addr::unchecked("mock_nft"), "a:b/c") ; "cw721 with separators in token id")]
+    fn key_round_trip(info: AssetInfo) {
+        let key = AssetInfoKey::from(&info);
+        assert_eq!(key, info);
+        assert_eq!(AssetInfo::from(key), info);
+    }
+
+    #[test_case(vec![] ; "empty")]
+    #[test_case(vec![1u8] ; "cw721 without length")]
+    #[test_case(vec![1u8, 0, 9, b'a'] ; "cw721 with short address")]
+    #[test_case(vec![0u8, 0xff, 0xfe] ; "invalid utf8")]
+    #[test_case(vec![2u8, b'a'] ; "unknown prefix")]
+    fn decoding_invalid_keys(bytes: Vec<u8>) {
+        assert!(AssetInfoKey::from_vec(bytes).is_err());
+    }
+
+    #[test]
+    fn cw721_keys_are_distinct() {
+        let nft = AssetInfo::cw721(Addr::unchecked("mock_nft"), "1");
+        assert_ne!(
+            AssetInfoKey::from(&nft),
+            AssetInfoKey::from(AssetInfo::cw721(Addr::unchecked("mock_nft1"), ""))
+        );
+        assert_ne!(
+            AssetInfoKey::from(&nft),
+            AssetInfoKey::from(AssetInfo::cw20(Addr::unchecked("mock_nft1")))
+        );
+    }
+
+    #[test_case("uosmo" => Ok(()); "base denom")]
+    #[test_case("ibc/27394FB092D2ECCD56123C74F36E4C1F926001CEADA9CA97EA622B25F41E5EB2" => Ok(()); "ibc denom")]
+    #[test_case("factory/osmo1creator/ulp" => Ok(()); "factory denom")]
+    #[test_case("gamm/pool/1" => Ok(()); "gamm denom")]
+    #[test_case("" => Err(StdError::generic_err("invalid denom : must be between 3 and 128 characters")); "empty")]
+    #[test_case("ab" => Err(StdError::generic_err("invalid denom ab: must be between 3 and 128 characters")); "too short")]
+    #[test_case(&"a".repeat(129) => matches Err(_); "too long")]
+    #[test_case("1uosmo" => Err(StdError::generic_err("invalid denom 1uosmo: must start with a letter")); "leading digit")]
+    #[test_case("u osmo" => Err(StdError::generic_err("invalid denom u osmo: contains invalid characters")); "whitespace")]
+    #[test_case("ibc/xyz" => Err(StdError::generic_err("invalid denom ibc/xyz: ibc hash must be 64 hex characters")); "invalid ibc denom")]
+    #[test_case("factory/osmo1creator" => matches Err(_); "factory denom without subdenom")]
+    fn validating_denoms(denom: &str) -> StdResult<()> {
+        validate_native_denom(denom)
+    }
+
+    #[test]
+    fn checking_with_options() {
+        let api = MockApi::default();
+        let querier: MockQuerier = MockQuerier::new(&[("alice", &[Coin::new(12345u128, "uosmo")])]);
+        let querier: QuerierWrapper = QuerierWrapper::new(&querier);
+
+        let options = CheckOptions::default();
+        let unchecked = AssetInfoUnchecked::native("uatom");
+        assert_eq!(
+            unchecked.check_with_options(&api, &querier, &options),
+            Ok(AssetInfo::native("uatom"))
+        );
+
+        let options = CheckOptions {
+            require_native_supply: true,
+        };
+        #[cfg(feature = "native-supply")]
+        {
+            assert_eq!(
+                unchecked.check_with_options(&api, &querier, &options),
+                Err(StdError::generic_err("denom has no supply: uatom"))
+            );
+            assert_eq!(
+                AssetInfoUnchecked::native("uosmo").check_with_options(&api, &querier, &options),
+                Ok(AssetInfo::native("uosmo"))
+            );
+        }
+        #[cfg(not(feature = "native-supply"))]
+        assert!(AssetInfoUnchecked::native("uosmo")
+            .check_with_options(&api, &querier, &options)
+            .is_err());
+        assert!(AssetInfoUnchecked::native("u osmo")
+            .check_with_options(&api, &querier, &options)
+            .is_err());
+    }
+
+    fn mock_querier(token: String, contract: String) -> MockQuerier {
+        let mut querier: MockQuerier = MockQuerier::new(&[]);
+        querier.update_wasm(move |query| match query {
+            WasmQuery::ContractInfo { contract_addr }
+                if *contract_addr == token || *contract_addr == contract =>
+            {
+                contract_info_result()
+            }
+            WasmQuery::Smart { contract_addr, .. } if *contract_addr == token => {
+                SystemResult::Ok(ContractResult::Ok(
+                    to_json_binary(&TokenInfoResponse {
+                        name: "Mock Token".to_string(),
+                        symbol: "MOCK".to_string(),
+                        decimals: 6,
+                        total_supply: Uint128::new(1_000_000),
+                    })
+                    .unwrap(),
+                ))
+            }
+            WasmQuery::Smart { .. } => {
+                SystemResult::Ok(ContractResult::Err("unknown query".to_string()))
+            }
+            WasmQuery::ContractInfo { contract_addr } => no_such_contract_result(contract_addr),
+            _ => panic!("[mock]: unsupported wasm query {:?}", query),
+        });
+        querier
+    }
+
+    #[test]
+    fn checking_with_querier() {
+        let api = MockApi::default();
+        let token = api.addr_make("mock_token");
+        let contract = api.addr_make("mock_contract");
+        let wallet = api.addr_make("alice");
+        let querier = mock_querier(token.to_string(), contract.to_string());
+        let querier: QuerierWrapper = QuerierWrapper::new(&querier);
+
+        assert_eq!(
+            AssetInfoUnchecked::cw20(token.as_str()).check_with_querier(&api, &querier),
+            Ok(AssetInfo::cw20(token))
+        );
+        assert_eq!(
+            AssetInfoUnchecked::native("uosmo").check_with_querier(&api, &querier),
+            Ok(AssetInfo::native("uosmo"))
+        );
+        assert_eq!(
+            AssetInfoUnchecked::cw20(wallet.as_str()).check_with_querier(&api, &querier),
+            Err(AssetError::NotAContract {
+                address: wallet.to_string()
+            })
+        );
+        assert_eq!(
+            AssetInfoUnchecked::cw20(contract.as_str()).check_with_querier(&api, &querier),
+            Err(AssetError::NotACw20 {
+                address: contract.to_string(),
+                error: StdError::generic_err("Querier contract error: unknown query"),
+            })
+        );
+        assert!(matches!(
+            AssetInfoUnchecked::cw20("Invalid").check_with_querier(&api, &querier),
+            Err(AssetError::Std(_))
+        ));
+
+        // only the existence of a cw721 contract is verified
+        assert_eq!(
+            AssetInfoUnchecked::cw721(contract.as_str(), "42").check_with_querier(&api, &querier),
+            Ok(AssetInfo::cw721(contract, "42"))
+        );
+        assert_eq!(
+            AssetInfoUnchecked::cw721(wallet.as_str(), "42").check_with_querier(&api, &querier),
+            Err(AssetError::NotAContract {
+                address: wallet.to_string()
+            })
+        );
+    }
+
+    #[test]
+    fn converting_bech32_prefix() {
+        let osmo = "osmo1h34lmpywh4upnjdg90cjf4j70aee6z8qqfspugamjp42e4q28kqsqgyqyr";
+        let neutron = "neutron1h34lmpywh4upnjdg90cjf4j70aee6z8qqfspugamjp42e4q28kqs56dudx";
+
+        let info = AssetInfoUnchecked::cw20(osmo);
+        assert_eq!(
+            info.with_bech32_prefix("neutron").unwrap(),
+            AssetInfoUnchecked::cw20(neutron)
+        );
+        assert_eq!(
+            info.with_bech32_prefix("neutron")
+                .unwrap()
+                .with_bech32_prefix("osmo")
+                .unwrap(),
+            info
+        );
+
+        let info = AssetInfoUnchecked::cw721(osmo, "42");
+        assert_eq!(
+            info.with_bech32_prefix("neutron").unwrap(),
+            AssetInfoUnchecked::cw721(neutron, "42")
+        );
+
+        let info = AssetInfoUnchecked::native("uosmo");
+        assert_eq!(info.with_bech32_prefix("neutron").unwrap(), info);
+
+        assert!(matches!(
+            AssetInfoUnchecked::cw20("mock_token").with_bech32_prefix("neutron"),
+            Err(AssetError::InvalidBech32Address { address, .. }) if address == "mock_token"
+        ));
+        assert!(matches!(
+            AssetInfoUnchecked::cw20(&osmo[..osmo.len() - 1]).with_bech32_prefix("neutron"),
+            Err(AssetError::InvalidBech32Address { .. })
+        ));
+        assert_eq!(
+            AssetInfoUnchecked::cw20(osmo).with_bech32_prefix(""),
+            Err(AssetError::InvalidBech32Prefix {
+                prefix: String::new()
+            })
+        );
     }
 
     #[test]
@@ -338,6 +840,25 @@ mod test {
         assert_eq!(addr, addr2);
     }
 
+    #[test]
+    fn converting_to_denom() {
+        let info = AssetInfo::cw20(Addr::unchecked("mock_token"));
+        assert_eq!(
+            Denom::try_from(info),
+            Ok(Denom::Cw20(Addr::unchecked("mock_token")))
+        );
+        assert_eq!(
+            Denom::try_from(AssetInfo::native("uusd")),
+            Ok(Denom::Native("uusd".to_string()))
+        );
+        assert_eq!(
+            Denom::try_from(AssetInfo::cw721(Addr::unchecked("mock_nft"), "42")),
+            Err(StdError::generic_err(
+                "Cannot convert CW721 asset info to Denom"
+            ))
+        );
+    }
+
     #[test]
     fn native_asset_info() {
         let info = AssetInfo::native("uusd");
@@ -378,11 +899,122 @@ mod test {
         assert_eq!(info2, AssetInfo::Native("uusd".to_string()));
     }
 
+    #[test]
+    #[cfg(feature = "astroport")]
+    fn from_astro_asset_info_for_unchecked() {
+        let info = astroport::asset::AssetInfo::Token {
+            contract_addr: Addr::unchecked("mock_token"),
+        };
+        let info2: AssetInfoUnchecked = info.into();
+        assert_eq!(info2, AssetInfoUnchecked::Cw20("mock_token".to_string()));
+    }
+
+    #[test]
+    #[cfg(feature = "terraswap")]
+    fn from_terraswap_asset_info() {
+        let api = MockApi::default();
+
+        let info = terraswap::asset::AssetInfo::Token {
+            contract_addr: "mock_token".to_string(),
+        };
+        let info2: AssetInfoUnchecked = info.into();
+        assert_eq!(info2, AssetInfoUnchecked::Cw20("mock_token".to_string()));
+        assert_eq!(
+            info2.check(&api).unwrap(),
+            AssetInfo::Cw20(Addr::unchecked("mock_token"))
+        );
+
+        let info = terraswap::asset::AssetInfo::Token {
+            contract_addr: "mt".to_string(),
+        };
+        assert!(AssetInfoUnchecked::from(info).check(&api).is_err());
+
+        let info = terraswap::asset::AssetInfo::NativeToken {
+            denom: "uusd".to_string(),
+        };
+        let info2: AssetInfoUnchecked = info.into();
+        assert_eq!(info2, AssetInfoUnchecked::Native("uusd".to_string()));
+    }
+
+    #[test]
+    #[cfg(feature = "terraswap")]
+    fn into_terraswap_asset_info() {
+        let info = AssetInfo::Cw20(Addr::unchecked("mock_token"));
+        let info2: terraswap::asset::AssetInfo = info.try_into().unwrap();
+        assert_eq!(
+            info2,
+            terraswap::asset::AssetInfo::Token {
+                contract_addr: "mock_token".to_string()
+            }
+        );
+
+        let info = AssetInfoUnchecked::Native("uusd".to_string());
+        let info2: terraswap::asset::AssetInfo = info.try_into().unwrap();
+        assert_eq!(
+            info2,
+            terraswap::asset::AssetInfo::NativeToken {
+                denom: "uusd".to_string()
+            }
+        );
+    }
+
+    #[test]
+    #[cfg(feature = "terraswap")]
+    fn nft_into_terraswap_asset_info() {
+        let info = AssetInfoUnchecked::cw721("mock_nft", "42");
+        assert!(terraswap::asset::AssetInfo::try_from(info).is_err());
+    }
+
+    #[test]
+    #[cfg(feature = "cw-asset-compat")]
+    fn cw_asset_asset_info_conversions() {
+        let info = AssetInfo::cw20(Addr::unchecked("mock_token"));
+        let upstream: cw_asset::AssetInfo = info.clone().try_into().unwrap();
+        assert_eq!(
+            upstream,
+            cw_asset::AssetInfo::cw20(Addr::unchecked("mock_token"))
+        );
+        assert_eq!(AssetInfo::try_from(upstream).unwrap(), info);
+
+        let info = AssetInfoUnchecked::native("uusd");
+        let upstream: cw_asset::AssetInfoUnchecked = info.clone().try_into().unwrap();
+        assert_eq!(upstream, cw_asset::AssetInfoUnchecked::native("uusd"));
+        assert_eq!(AssetInfoUnchecked::try_from(upstream).unwrap(), info);
+
+        let upstream = cw_asset::AssetInfo::cw1155(Addr::unchecked("mock_nft"), "1");
+        assert_eq!(
+            AssetInfo::try_from(upstream),
+            Err(AssetError::UnsupportedCw1155 {
+                contract_addr: "mock_nft".to_string(),
+                token_id: "1".to_string(),
+            })
+        );
+
+        let info = AssetInfo::cw721(Addr::unchecked("mock_nft"), "1");
+        assert!(cw_asset::AssetInfo::try_from(info).is_err());
+    }
+
+    #[test]
+    #[cfg(feature = "cw-asset-compat")]
+    fn cw_asset_asset_info_serde_compat() {
+        use cosmwasm_std::{from_json, to_json_vec};
+
+        for info in [
+            AssetInfo::cw20(Addr::unchecked("mock_token")),
+            AssetInfo::native("uusd"),
+        ] {
+            let upstream: cw_asset::AssetInfo = info.clone().try_into().unwrap();
+            let json = to_json_vec(&info).unwrap();
+            assert_eq!(json, to_json_vec(&upstream).unwrap());
+            assert_eq!(from_json::<cw_asset::AssetInfo>(&json).unwrap(), upstream);
+        }
+    }
+
     #[test]
     #[cfg(feature = "astroport")]
     fn into_astro_asset_info() {
         let info = AssetInfo::Cw20(Addr::unchecked("mock_token"));
-        let info2: astroport::asset::AssetInfo = info.into();
+        let info2: astroport::asset::AssetInfo = info.try_into().unwrap();
         assert_eq!(
             info2,
             astroport::asset::AssetInfo::Token {
@@ -391,7 +1023,7 @@ mod test {
         );
 
         let info = AssetInfo::Native("uusd".to_string());
-        let info2: astroport::asset::AssetInfo = info.into();
+        let info2: astroport::asset::AssetInfo = info.try_into().unwrap();
         assert_eq!(
             info2,
             astroport::asset::AssetInfo::NativeToken {
@@ -399,4 +1031,16 @@ mod test {
             }
         );
     }
+
+    #[test]
+    #[cfg(feature = "astroport")]
+    fn nft_into_astro_asset_info() {
+        let info = AssetInfo::cw721(Addr::unchecked("mock_nft"), "42");
+        assert_eq!(
+            astroport::asset::AssetInfo::try_from(info),
+            Err(StdError::generic_err(
+                "Cannot convert CW721 asset info to Astroport asset info"
+            ))
+        );
+    }
 }
@@ -0,0 +1,273 @@
+use std::convert::TryFrom;
+use std::fmt;
+
+use cosmwasm_std::{StdError, StdResult};
+use cw_storage_plus::{Key, KeyDeserialize, Prefixer, PrimaryKey};
+
+use schemars::gen::SchemaGenerator;
+use schemars::schema::Schema;
+use schemars::JsonSchema;
+use serde::{Deserialize, Serialize};
+
+use super::asset_info::{AssetInfo, AssetInfoKey};
+
+/// An unordered pair of two different assets, e.g. the assets of a pool
+///
+/// The members are sorted canonically by their `AssetInfoKey` bytes, so that a
+/// pair created from the same assets in either order is equal, and has the
+/// same storage key.
+///
+/// **Usage:**
+/// The following code stores a pool address under a pair of assets, which can
+/// be loaded with the assets in either order.
+///
+/// ```rust
+/// const POOLS: Map<AssetInfoPair, Addr> = Map::new("pools");
+///
+/// let pair = AssetInfoPair::new(AssetInfo::native("uosmo"), AssetInfo::native("uatom"))?;
+/// POOLS.save(deps.storage, pair, &pool_addr)?;
+/// ```
+#[derive(Serialize, Deserialize, Clone, Debug, PartialEq, Eq, Hash)]
+#[serde(try_from = "[AssetInfo; 2]", into = "[AssetInfo; 2]")]
+pub struct AssetInfoPair {
+    infos: [AssetInfo; 2],
+    keys: [AssetInfoKey; 2],
+}
+
+impl AssetInfoPair {
+    /// Create a new pair of the given assets in canonical order
+    ///
+    /// Returns an error if both assets are the same.
+    pub fn new(a: AssetInfo, b: AssetInfo) -> StdResult<Self> {
+        let (key_a, key_b) = (AssetInfoKey::from(&a), AssetInfoKey::from(&b));
+        if key_a == key_b {
+            return Err(StdError::generic_err(format!(
+                "AssetInfoPair must contain two different assets, but both are {}",
+                a
+            )));
+        }
+        Ok(if key_a.as_bytes() < key_b.as_bytes() {
+            Self {
+                infos: [a, b],
+                keys: [key_a, key_b],
+            }
+        } else {
+            Self {
+                infos: [b, a],
+                keys: [key_b, key_a],
+            }
+        })
+    }
+
+    /// Return the member that sorts first
+    pub fn first(&self) -> &AssetInfo {
+        &self.infos[0]
+    }
+
+    /// Return the member that sorts second
+    pub fn second(&self) -> &AssetInfo {
+        &self.infos[1]
+    }
+
+    /// Whether the asset is a member of the pair
+    pub fn contains(&self, info: &AssetInfo) -> bool {
+        self.infos.contains(info)
+    }
+
+    /// Return the member of the pair other than the given asset, or `None` if
+    /// the asset is not a member of the pair
+    pub fn other(&self, info: &AssetInfo) -> Option<&AssetInfo> {
+        match &self.infos {
+            [first, second] if first == info => Some(second),
+            [first, second] if second == info => Some(first),
+            _ => None,
+        }
+    }
+}
+
+impl TryFrom<[AssetInfo; 2]> for AssetInfoPair {
+    type Error = StdError;
+
+    fn try_from(infos: [AssetInfo; 2]) -> StdResult<Self> {
+        let [a, b] = infos;
+        Self::new(a, b)
+    }
+}
+
+impl From<AssetInfoPair> for [AssetInfo; 2] {
+    fn from(pair: AssetInfoPair) -> Self {
+        pair.infos
+    }
+}
+
+// the pair is (de)serialized as `[AssetInfo; 2]`, which the derived schema
+// would not reflect
+impl JsonSchema for AssetInfoPair {
+    fn schema_name() -> String {
+        "AssetInfoPair".to_string()
+    }
+
+    fn json_schema(gen: &mut SchemaGenerator) -> Schema {
+        <[AssetInfo; 2]>::json_schema(gen)
+    }
+}
+
+impl fmt::Display for AssetInfoPair {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        write!(f, "{}-{}", self.infos[0], self.infos[1])
+    }
+}
+
+impl<'a> PrimaryKey<'a> for AssetInfoPair {
+    type Prefix = AssetInfoKey;
+    type SubPrefix = ();
+    type Suffix = AssetInfoKey;
+    type SuperSuffix = Self;
+
+    fn key(&self) -> Vec<Key<'_>> {
+        vec![
+            Key::Ref(self.keys[0].as_bytes()),
+            Key::Ref(self.keys[1].as_bytes()),
+        ]
+    }
+}
+
+impl<'a> Prefixer<'a> for AssetInfoPair {
+    fn prefix(&self) -> Vec<Key<'_>> {
+        self.key()
+    }
+}
+
+impl KeyDeserialize for AssetInfoPair {
+    type Output = Self;
+
+    #[cfg(not(feature = "cosmwasm-1"))]
+    const KEY_ELEMS: u16 = 2;
+
+    fn from_vec(value: Vec<u8>) -> StdResult<Self::Output> {
+        let (a, b) = <(AssetInfoKey, AssetInfoKey)>::from_vec(value)?;
+        Self::new(a.into(), b.into())
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::compat::Map;
+    use cosmwasm_std::testing::MockStorage;
+    use cosmwasm_std::{from_json, to_json_vec, Addr, Order};
+
+    use test_case::test_case;
+
+    fn uosmo() -> AssetInfo {
+        AssetInfo::native("uosmo")
+    }
+
+    fn mock_token() -> AssetInfo {
+        AssetInfo::cw20(Addr::unchecked("mock_token"))
+    }
+
+    #[test_case(uosmo(), mock_token() ; "native and cw20")]
+    #[test_case(AssetInfo::native("uatom"), uosmo() ; "two natives")]
+    #[test_case(mock_token(), AssetInfo::cw721(Addr::unchecked("mock_token"), "1") ; "cw20 and cw721")]
+    fn ordering(a: AssetInfo, b: AssetInfo) {
+        let pair = AssetInfoPair::new(a.clone(), b.clone()).unwrap();
+        assert_eq!(pair, AssetInfoPair::new(b.clone(), a.clone()).unwrap());
+        assert!(
+            AssetInfoKey::from(pair.first()).as_bytes()
+                < AssetInfoKey::from(pair.second()).as_bytes()
+        );
+        assert!(pair.contains(&a) && pair.contains(&b));
+        assert_eq!(pair.other(&a), Some(&b));
+        assert_eq!(pair.other(&b), Some(&a));
+        assert_eq!(pair.other(&AssetInfo::native("uusd")), None);
+    }
+
+    #[test]
+    fn rejecting_identical_members() {
+        assert_eq!(
+            AssetInfoPair::new(uosmo(), uosmo()),
+            Err(StdError::generic_err(
+                "AssetInfoPair must contain two different assets, but both are uosmo"
+            ))
+        );
+        assert!(AssetInfoPair::try_from([mock_token(), mock_token()]).is_err());
+    }
+
+    #[test]
+    fn decoding_invalid_keys() {
+        // a valid first member followed by a truncated cw721 key
+        let first = AssetInfoKey::from(uosmo());
+        let mut bytes = (first.as_bytes().len() as u16).to_be_bytes().to_vec();
+        bytes.extend_from_slice(first.as_bytes());
+        bytes.push(1u8);
+        assert!(AssetInfoPair::from_vec(bytes).is_err());
+    }
+
+    #[test]
+    fn converting() {
+        let pair = AssetInfoPair::try_from([uosmo(), mock_token()]).unwrap();
+        assert_eq!(
+            <[AssetInfo; 2]>::from(pair.clone()),
+            [mock_token(), uosmo()]
+        );
+        assert_eq!(pair.to_string(), "mock_token-uosmo");
+    }
+
+    #[test]
+    fn serializing() {
+        let pair = AssetInfoPair::new(uosmo(), mock_token()).unwrap();
+        let json = to_json_vec(&pair).unwrap();
+        assert_eq!(json, to_json_vec(&[mock_token(), uosmo()]).unwrap());
+        assert_eq!(from_json::<AssetInfoPair>(&json).unwrap(), pair);
+
+        // unordered input is sorted, identical members are rejected
+        let json = to_json_vec(&[uosmo(), mock_token()]).unwrap();
+        assert_eq!(from_json::<AssetInfoPair>(&json).unwrap(), pair);
+        let json = to_json_vec(&[uosmo(), uosmo()]).unwrap();
+        assert!(from_json::<AssetInfoPair>(&json).is_err());
+    }
+
+    #[test]
+    fn storing() {
+        let mut storage = MockStorage::default();
+        let pools: Map<AssetInfoPair, String> = Map::new("pools");
+        let uatom = AssetInfo::native("uatom");
+
+        let pair = AssetInfoPair::new(uosmo(), mock_token()).unwrap();
+        pools
+            .save(&mut storage, pair, &"pool1".to_string())
+            .unwrap();
+        let pair = AssetInfoPair::new(uosmo(), uatom.clone()).unwrap();
+        pools
+            .save(&mut storage, pair, &"pool2".to_string())
+            .unwrap();
+
+        let pair = AssetInfoPair::new(mock_token(), uosmo()).unwrap();
+        assert_eq!(pools.load(&storage, pair).unwrap(), "pool1");
+
+        let pairs = pools
+            .keys(&storage, None, None, Order::Ascending)
+            .collect::<StdResult<Vec<_>>>()
+            .unwrap();
+        // keys are ordered by the length of the first member's key first
+        assert_eq!(
+            pairs,
+            vec![
+                AssetInfoPair::new(uosmo(), uatom.clone()).unwrap(),
+                AssetInfoPair::new(uosmo(), mock_token()).unwrap(),
+            ]
+        );
+
+        // all pairs whose first member is uatom
+        let pools_with_uatom = pools
+            .prefix(AssetInfoKey::from(&uatom))
+            .range(&storage, None, None, Order::Ascending)
+            .collect::<StdResult<Vec<_>>>()
+            .unwrap();
+        assert_eq!(
+            pools_with_uatom,
+            vec![(AssetInfoKey::from(uosmo()), "pool2".to_string())]
+        );
+    }
+}
@@ -0,0 +1,306 @@
+use cosmwasm_std::{Addr, CosmosMsg, Order, StdError, StdResult, Storage, Uint128};
+
+use super::asset::Asset;
+use super::asset_info::{AssetInfo, AssetInfoKey};
+use super::asset_list::AssetList;
+use super::compat::Map;
+
+/// An internal balance book stored in contract state, tracking how much of each
+/// asset every address owns, as well as the total of each asset across all
+/// addresses
+///
+/// **Usage:**
+/// The following code credits a user's deposit, and later pays out a
+/// withdrawal from their internal balance.
+///
+/// ```rust
+/// const LEDGER: AssetLedger = AssetLedger::new("balances", "totals");
+///
+/// LEDGER.credit(deps.storage, &info.sender, &deposit)?;
+/// let msgs = LEDGER.withdraw_msgs(deps.storage, &info.sender, &withdrawal)?;
+/// ```
+pub struct AssetLedger<'a> {
+    balances: Map<'a, (&'a Addr, AssetInfoKey), Uint128>,
+    totals: Map<'a, AssetInfoKey, Uint128>,
+}
+
+impl<'a> AssetLedger<'a> {
+    /// Create a new ledger storing per-address balances and per-asset totals
+    /// under the given storage namespaces
+    pub const fn new(balances_namespace: &'static str, totals_namespace: &'static str) -> Self {
+        Self {
+            balances: Map::new(balances_namespace),
+            totals: Map::new(totals_namespace),
+        }
+    }
+
+    /// Return an address' balance of the asset
+    pub fn balance(
+        &self,
+        storage: &dyn Storage,
+        addr: &Addr,
+        info: &AssetInfo,
+    ) -> StdResult<Uint128> {
+        Ok(self
+            .balances
+            .may_load(storage, (addr, info.into()))?
+            .unwrap_or_default())
+    }
+
+    /// Return all non-zero balances of an address
+    pub fn balances(&self, storage: &dyn Storage, addr: &Addr) -> StdResult<AssetList> {
+        self.balances
+            .prefix(addr)
+            .range(storage, None, None, Order::Ascending)
+            .map(|item| item.map(|(key, amount)| Asset::new(key.into(), amount)))
+            .collect::<StdResult<Vec<Asset>>>()
+            .and_then(AssetList::try_from_assets)
+    }
+
+    /// Return the sum of all addresses' balances of the asset
+    pub fn total(&self, storage: &dyn Storage, info: &AssetInfo) -> StdResult<Uint128> {
+        Ok(self
+            .totals
+            .may_load(storage, info.into())?
+            .unwrap_or_default())
+    }
+
+    /// Increase an address' balance by the asset's amount, and return the new
+    /// balance
+    ///
+    /// Returns an error if the balance or total of a CW721 NFT would exceed 1.
+    pub fn credit(
+        &self,
+        storage: &mut dyn Storage,
+        addr: &Addr,
+        asset: &Asset,
+    ) -> StdResult<Uint128> {
+        let balance = self
+            .balance(storage, addr, &asset.info)?
+            .checked_add(asset.amount)?;
+        let total = self
+            .total(storage, &asset.info)?
+            .checked_add(asset.amount)?;
+        Asset::new(asset.info.clone(), balance).check_nft_amount()?;
+        Asset::new(asset.info.clone(), total).check_nft_amount()?;
+        self.save(storage, addr, &asset.info, balance, total)?;
+        Ok(balance)
+    }
+
+    /// Decrease an address' balance by the asset's amount, and return the new
+    /// balance
+    ///
+    /// Returns an error if the address' balance is insufficient.
+    pub fn debit(
+        &self,
+        storage: &mut dyn Storage,
+        addr: &Addr,
+        asset: &Asset,
+    ) -> StdResult<Uint128> {
+        let balance = self.balance(storage, addr, &asset.info)?;
+        let balance = balance.checked_sub(asset.amount).map_err(|_| {
+            StdError::generic_err(format!(
+                "insufficient balance: {} < {}",
+                Asset::new(asset.info.clone(), balance),
+                asset
+            ))
+        })?;
+        let total = self
+            .total(storage, &asset.info)?
+            .checked_sub(asset.amount)?;
+        self.save(storage, addr, &asset.info, balance, total)?;
+        Ok(balance)
+    }
+
+    /// Debit every asset in the list from an address' balance, and generate
+    /// messages that transfer them to the address
+    pub fn withdraw_msgs(
+        &self,
+        storage: &mut dyn Storage,
+        addr: &Addr,
+        assets: &AssetList,
+    ) -> StdResult<Vec<CosmosMsg>> {
+        self.withdraw_msgs_custom(storage, addr, assets)
+    }
+
+    /// Like `withdraw_msgs`, but for a contract using custom messages
+    pub fn withdraw_msgs_custom<T>(
+        &self,
+        storage: &mut dyn Storage,
+        addr: &Addr,
+        assets: &AssetList,
+    ) -> StdResult<Vec<CosmosMsg<T>>> {
+        for asset in assets {
+            self.debit(storage, addr, asset)?;
+        }
+        assets.transfer_msgs_custom(addr)
+    }
+
+    fn save(
+        &self,
+        storage: &mut dyn Storage,
+        addr: &Addr,
+        info: &AssetInfo,
+        balance: Uint128,
+        total: Uint128,
+    ) -> StdResult<()> {
+        if balance.is_zero() {
+            self.balances.remove(storage, (addr, info.into()));
+        } else {
+            self.balances.save(storage, (addr, info.into()), &balance)?;
+        }
+        if total.is_zero() {
+            self.totals.remove(storage, info.into());
+        } else {
+            self.totals.save(storage, info.into(), &total)?;
+        }
+        Ok(())
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use cosmwasm_std::testing::MockStorage;
+    use cosmwasm_std::{BankMsg, Coin};
+
+    const LEDGER: AssetLedger = AssetLedger::new("balances", "totals");
+
+    fn mock_token() -> AssetInfo {
+        AssetInfo::cw20(Addr::unchecked("mock_token"))
+    }
+
+    #[test]
+    fn crediting_and_debiting() {
+        let mut storage = MockStorage::default();
+        let alice = Addr::unchecked("alice");
+        let bob = Addr::unchecked("bob");
+        let uusd = AssetInfo::native("uusd");
+
+        let balance = LEDGER
+            .credit(&mut storage, &alice, &Asset::new(uusd.clone(), 100u128))
+            .unwrap();
+        assert_eq!(balance, Uint128::new(100));
+        LEDGER
+            .credit(&mut storage, &bob, &Asset::new(uusd.clone(), 50u128))
+            .unwrap();
+        assert_eq!(LEDGER.total(&storage, &uusd).unwrap(), Uint128::new(150));
+
+        let balance = LEDGER
+            .debit(&mut storage, &alice, &Asset::new(uusd.clone(), 30u128))
+            .unwrap();
+        assert_eq!(balance, Uint128::new(70));
+        assert_eq!(LEDGER.total(&storage, &uusd).unwrap(), Uint128::new(120));
+
+        let err = LEDGER.debit(&mut storage, &bob, &Asset::new(uusd.clone(), 51u128));
+        assert_eq!(
+            err,
+            Err(StdError::generic_err(
+                "insufficient balance: uusd:50 < uusd:51"
+            ))
+        );
+
+        let err = LEDGER.debit(&mut storage, &bob, &Asset::new(mock_token(), 1u128));
+        assert_eq!(
+            err,
+            Err(StdError::generic_err(
+                "insufficient balance: mock_token:0 < mock_token:1"
+            ))
+        );
+    }
+
+    #[test]
+    fn crediting_nfts() {
+        let mut storage = MockStorage::default();
+        let alice = Addr::unchecked("alice");
+        let bob = Addr::unchecked("bob");
+        let nft = Asset::cw721(Addr::unchecked("mock_nft"), "1");
+
+        LEDGER.credit(&mut storage, &alice, &nft).unwrap();
+        let err = LEDGER.credit(&mut storage, &alice, &nft);
+        assert_eq!(
+            err,
+            Err(StdError::generic_err(
+                "cw721 asset amount must be 0 or 1: mock_nft:1:2"
+            ))
+        );
+        assert!(LEDGER.credit(&mut storage, &bob, &nft).is_err());
+
+        assert_eq!(
+            LEDGER.balances(&storage, &alice).unwrap(),
+            AssetList::try_from_assets(vec![nft.clone()]).unwrap()
+        );
+        assert_eq!(LEDGER.total(&storage, &nft.info).unwrap(), Uint128::one());
+    }
+
+    #[test]
+    fn querying_balances() {
+        let mut storage = MockStorage::default();
+        let alice = Addr::unchecked("alice");
+        let bob = Addr::unchecked("bob");
+
+        LEDGER
+            .credit(&mut storage, &alice, &Asset::native("uusd", 69420u128))
+            .unwrap();
+        LEDGER
+            .credit(&mut storage, &alice, &Asset::new(mock_token(), 88888u128))
+            .unwrap();
+        LEDGER
+            .credit(&mut storage, &bob, &Asset::native("uluna", 12345u128))
+            .unwrap();
+
+        assert_eq!(
+            LEDGER.balances(&storage, &alice).unwrap(),
+            AssetList::try_from_assets(vec![
+                Asset::new(mock_token(), 88888u128),
+                Asset::native("uusd", 69420u128),
+            ])
+            .unwrap()
+        );
+
+        LEDGER
+            .debit(&mut storage, &alice, &Asset::native("uusd", 69420u128))
+            .unwrap();
+        assert_eq!(
+            LEDGER.balances(&storage, &alice).unwrap(),
+            AssetList::try_from_assets(vec![Asset::new(mock_token(), 88888u128)]).unwrap()
+        );
+        assert_eq!(
+            LEDGER.total(&storage, &AssetInfo::native("uusd")).unwrap(),
+            Uint128::zero()
+        );
+    }
+
+    #[test]
+    fn withdrawing() {
+        let mut storage = MockStorage::default();
+        let alice = Addr::unchecked("alice");
+
+        LEDGER
+            .credit(&mut storage, &alice, &Asset::native("uusd", 69420u128))
+            .unwrap();
+
+        let withdrawal = AssetList::try_from_assets(vec![Asset::native("uusd", 420u128)]).unwrap();
+        let msgs = LEDGER
+            .withdraw_msgs(&mut storage, &alice, &withdrawal)
+            .unwrap();
+        assert_eq!(
+            msgs,
+            vec![CosmosMsg::Bank(BankMsg::Send {
+                to_address: String::from("alice"),
+                amount: vec![Coin::new(420u128, "uusd")]
+            })]
+        );
+        assert_eq!(
+            LEDGER
+                .balance(&storage, &alice, &AssetInfo::native("uusd"))
+                .unwrap(),
+            Uint128::new(69000)
+        );
+
+        let withdrawal = AssetList::try_from_assets(vec![Asset::new(mock_token(), 1u128)]).unwrap();
+        assert!(LEDGER
+            .withdraw_msgs(&mut storage, &alice, &withdrawal)
+            .is_err());
+    }
+}
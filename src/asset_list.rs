@@ -2,15 +2,16 @@ use std::convert::{TryFrom, TryInto};
 use std::fmt;
 use std::slice::{Iter, IterMut};
 
-use cosmwasm_std::{Addr, Api, Coin, CosmosMsg, QuerierWrapper, StdError, StdResult};
+use cosmwasm_std::{Addr, Api, Attribute, Coin, CosmosMsg, Event, StdError, StdResult, Uint128};
 
 use schemars::JsonSchema;
 use serde::{Deserialize, Serialize};
 
-use crate::AssetUnchecked;
+use crate::{AssetError, AssetQuerier, AssetUnchecked, PriceSource};
 
 use super::asset::{Asset, AssetBase};
 use super::asset_info::AssetInfo;
+use super::events::{attribute_key, get_attribute, AssetEventKind, ASSET_EVENT_PREFIX};
 
 #[derive(Serialize, Deserialize, Clone, Debug, PartialEq, Eq, JsonSchema)]
 pub struct AssetListBase<T>(pub(crate) Vec<AssetBase<T>>);
@@ -26,13 +27,22 @@ pub type AssetListUnchecked = AssetListBase<String>;
 pub type AssetList = AssetListBase<Addr>;
 
 #[cfg(feature = "astroport")]
-impl From<AssetList> for Vec<astroport::asset::Asset> {
-    fn from(value: AssetList) -> Self {
+impl TryFrom<AssetList> for Vec<astroport::asset::Asset> {
+    type Error = StdError;
+
+    fn try_from(value: AssetList) -> StdResult<Self> {
         value
             .0
             .into_iter()
-            .map(|asset| asset.into())
-            .collect::<Vec<astroport::asset::Asset>>()
+            .map(|asset| asset.try_into())
+            .collect::<StdResult<Vec<astroport::asset::Asset>>>()
+    }
+}
+
+#[cfg(feature = "astroport")]
+impl From<Vec<astroport::asset::Asset>> for AssetListUnchecked {
+    fn from(value: Vec<astroport::asset::Asset>) -> Self {
+        Self(value.into_iter().map(|asset| asset.into()).collect())
     }
 }
 
@@ -54,9 +64,25 @@ impl From<AssetList> for AssetListUnchecked {
     }
 }
 
+mod sealed {
+    /// Types that convert into an `Asset` that is never a CW721 NFT, so that
+    /// any number of them can be merged into an `AssetList`
+    pub trait Fungible: Into<super::Asset> {}
+
+    impl Fungible for cosmwasm_std::Coin {}
+    impl Fungible for &cosmwasm_std::Coin {}
+    impl Fungible for cw20::Cw20CoinVerified {}
+    #[cfg(feature = "astroport")]
+    impl Fungible for astroport::asset::Asset {}
+}
+
+/// Merge coins or other fungible assets into an asset list
+///
+/// Assets that may be NFTs are merged with `AssetList::try_from_assets`
+/// instead, as merging them can fail.
 impl<A, B> From<B> for AssetList
 where
-    A: Into<Asset>,
+    A: sealed::Fungible,
     B: IntoIterator<Item = A>,
 {
     fn from(list: B) -> Self {
@@ -106,7 +132,133 @@ impl TryFrom<AssetList> for Vec<Coin> {
     }
 }
 
+#[cfg(feature = "terraswap")]
+impl TryFrom<AssetList> for Vec<terraswap::asset::Asset> {
+    type Error = StdError;
+
+    fn try_from(value: AssetList) -> StdResult<Self> {
+        value
+            .0
+            .into_iter()
+            .map(|asset| asset.try_into())
+            .collect::<StdResult<Vec<terraswap::asset::Asset>>>()
+    }
+}
+
+#[cfg(feature = "terraswap")]
+impl TryFrom<AssetListUnchecked> for Vec<terraswap::asset::Asset> {
+    type Error = StdError;
+
+    fn try_from(value: AssetListUnchecked) -> StdResult<Self> {
+        value
+            .0
+            .into_iter()
+            .map(|asset| asset.try_into())
+            .collect::<StdResult<Vec<terraswap::asset::Asset>>>()
+    }
+}
+
+#[cfg(feature = "terraswap")]
+impl From<Vec<terraswap::asset::Asset>> for AssetListUnchecked {
+    fn from(value: Vec<terraswap::asset::Asset>) -> Self {
+        Self(value.into_iter().map(|asset| asset.into()).collect())
+    }
+}
+
+#[cfg(feature = "cw-asset-compat")]
+impl AssetList {
+    /// Create a new asset list from an upstream `cw_asset::AssetList`
+    ///
+    /// Returns an error if the list contains an asset that this crate does
+    /// not support, e.g. a CW1155 token.
+    ///
+    /// NOTE: `TryFrom` cannot be implemented for this conversion, as it
+    /// conflicts with the generic `From` implementation for `AssetList`.
+    pub fn try_from_cw_asset_list(list: &cw_asset::AssetList) -> Result<Self, AssetError> {
+        list.into_iter()
+            .cloned()
+            .map(Asset::try_from)
+            .collect::<Result<Vec<Asset>, AssetError>>()
+            .and_then(|assets| Ok(AssetList::try_from_assets(assets)?))
+    }
+}
+
+#[cfg(feature = "cw-asset-compat")]
+impl TryFrom<AssetList> for cw_asset::AssetList {
+    type Error = StdError;
+
+    fn try_from(list: AssetList) -> StdResult<Self> {
+        list.0
+            .into_iter()
+            .map(TryInto::try_into)
+            .collect::<StdResult<Vec<cw_asset::Asset>>>()
+            .map(Into::into)
+    }
+}
+
+#[cfg(feature = "osmosis")]
+impl AssetList {
+    /// Create a new asset list from Osmosis proto coins
+    ///
+    /// NOTE: `TryFrom` cannot be implemented for this conversion, as it
+    /// conflicts with the generic `From` implementation for `AssetList`.
+    pub fn try_from_proto_coins(
+        coins: Vec<osmosis_std::types::cosmos::base::v1beta1::Coin>,
+    ) -> StdResult<Self> {
+        coins
+            .into_iter()
+            .map(Asset::try_from)
+            .collect::<StdResult<Vec<Asset>>>()
+            .and_then(AssetList::try_from_assets)
+    }
+}
+
+#[cfg(feature = "osmosis")]
+impl TryFrom<AssetList> for Vec<osmosis_std::types::cosmos::base::v1beta1::Coin> {
+    type Error = StdError;
+
+    fn try_from(list: AssetList) -> StdResult<Self> {
+        (&list).try_into()
+    }
+}
+
+#[cfg(feature = "osmosis")]
+impl TryFrom<&AssetList> for Vec<osmosis_std::types::cosmos::base::v1beta1::Coin> {
+    type Error = StdError;
+
+    fn try_from(list: &AssetList) -> StdResult<Self> {
+        list.iter()
+            .map(|asset| asset.try_into())
+            .collect::<StdResult<Self>>()
+    }
+}
+
 impl AssetListUnchecked {
+    /// Create a new, empty unchecked asset list
+    pub fn new() -> Self {
+        AssetListBase::default()
+    }
+
+    /// Append an asset to the end of the list
+    ///
+    /// Unlike `AssetList::add`, no merging or purging is done, so the list may
+    /// contain duplicates or zero amounts until it is checked.
+    pub fn push(&mut self, asset: AssetUnchecked) -> &mut Self {
+        self.0.push(asset);
+        self
+    }
+
+    /// Return length of the asset list
+    #[allow(clippy::len_without_is_empty)]
+    pub fn len(&self) -> usize {
+        self.0.len()
+    }
+
+    /// Returns an iterator over the asset list
+    pub fn iter(&self) -> Iter<'_, AssetUnchecked> {
+        self.0.iter()
+    }
+
     /// Validate contract address of every asset in the list, and return a new
     /// `AssetList` instance
     pub fn check(&self, api: &dyn Api) -> StdResult<AssetList> {
@@ -116,6 +268,95 @@ impl AssetListUnchecked {
         }
         Ok(assets)
     }
+
+    /// Re-encode the bech32 address of every CW20 token in the list with the
+    /// given prefix, e.g. to reuse a config on another chain
+    pub fn with_bech32_prefix(&self, prefix: &str) -> Result<Self, AssetError> {
+        self.iter()
+            .map(|asset| asset.with_bech32_prefix(prefix))
+            .collect::<Result<Vec<_>, _>>()
+            .map(AssetListBase)
+    }
+
+    /// Validate every asset in the list like `check`, and additionally verify
+    /// that the address of every CW20 token belongs to a CW20 contract
+    pub fn check_with_querier(
+        &self,
+        api: &dyn Api,
+        querier: &dyn AssetQuerier,
+    ) -> Result<AssetList, AssetError> {
+        let mut assets = AssetList::default();
+        for asset in &self.0 {
+            assets.add(&asset.check_with_querier(api, querier)?)?;
+        }
+        Ok(assets)
+    }
+
+    /// Validate every asset in the list like `check`, but return an error if
+    /// the list contains an asset with zero amount or more than one asset of
+    /// the same kind, instead of purging or merging them
+    pub fn check_strict(&self, api: &dyn Api) -> StdResult<AssetList> {
+        let mut assets = AssetList::default();
+        for asset in &self.0 {
+            if asset.amount.is_zero() {
+                return Err(StdError::generic_err(format!(
+                    "zero amount: {}",
+                    asset.info
+                )));
+            }
+            let checked = asset.check(api)?;
+            if assets.find(&checked.info).is_some() {
+                return Err(StdError::generic_err(format!(
+                    "duplicate asset: {}",
+                    checked.info
+                )));
+            }
+            assets.add(&checked)?;
+        }
+        Ok(assets)
+    }
+
+    /// Validate every asset in the list like `check`, and return an error if
+    /// any of the assets is not in the given allowlist
+    pub fn check_with_allowlist(
+        &self,
+        api: &dyn Api,
+        allowlist: &[AssetInfo],
+    ) -> StdResult<AssetList> {
+        let assets = self.check(api)?;
+        for asset in &assets {
+            if !allowlist.contains(&asset.info) {
+                return Err(StdError::generic_err(format!(
+                    "asset not allowed: {}",
+                    asset.info
+                )));
+            }
+        }
+        Ok(assets)
+    }
+}
+
+impl fmt::Display for AssetListUnchecked {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        write!(
+            f,
+            "{}",
+            self.0
+                .iter()
+                .map(|asset| asset.to_string())
+                .collect::<Vec<String>>()
+                .join(",")
+        )
+    }
+}
+
+impl<'a> IntoIterator for &'a AssetListUnchecked {
+    type Item = &'a AssetUnchecked;
+    type IntoIter = std::slice::Iter<'a, AssetUnchecked>;
+
+    fn into_iter(self) -> Self::IntoIter {
+        self.0.iter()
+    }
 }
 
 impl fmt::Display for AssetList {
@@ -142,6 +383,22 @@ impl<'a> IntoIterator for &'a AssetList {
 }
 
 impl AssetList {
+    /// Create a new asset list from the given assets, merging assets of the
+    /// same kind
+    ///
+    /// Returns an error if the amount of a CW721 NFT would exceed 1, or if an
+    /// amount would overflow.
+    ///
+    /// NOTE: `TryFrom` cannot be implemented for this conversion, as it
+    /// conflicts with the generic `From` implementation for `AssetList`.
+    pub fn try_from_assets<B: IntoIterator<Item = Asset>>(assets: B) -> StdResult<Self> {
+        let mut asset_list = AssetList::default();
+        for asset in assets {
+            asset_list.add(&asset)?;
+        }
+        Ok(asset_list)
+    }
+
     /// Create a new, empty asset list
     pub fn new() -> Self {
         AssetListBase::default()
@@ -159,12 +416,12 @@ impl AssetList {
     }
 
     /// Returns an iterator over the asset list
-    pub fn iter(&self) -> Iter<Asset> {
+    pub fn iter(&self) -> Iter<'_, Asset> {
         self.0.iter()
     }
 
     /// Returns a mutable iterator over the asset list
-    pub fn iter_mut(&mut self) -> IterMut<Asset> {
+    pub fn iter_mut(&mut self) -> IterMut<'_, Asset> {
         self.0.iter_mut()
     }
 
@@ -179,11 +436,7 @@ impl AssetList {
         self.iter()
             .filter_map(|a| {
                 let native: StdResult<Coin> = a.try_into();
-                if let Ok(coin) = native {
-                    Some(coin)
-                } else {
-                    None
-                }
+                native.ok()
             })
             .collect()
     }
@@ -212,6 +465,8 @@ impl AssetList {
     ///
     /// If asset of the same kind already exists in the list, then increment its
     /// amount; if not, append to the end of the list.
+    ///
+    /// Returns an error if the amount of a CW721 NFT would exceed 1.
     pub fn add(&mut self, asset_to_add: &Asset) -> StdResult<&mut Self> {
         match self
             .0
@@ -219,9 +474,15 @@ impl AssetList {
             .find(|asset| asset.info == asset_to_add.info)
         {
             Some(asset) => {
-                asset.amount = asset.amount.checked_add(asset_to_add.amount)?;
+                let sum = Asset::new(
+                    asset.info.clone(),
+                    asset.amount.checked_add(asset_to_add.amount)?,
+                );
+                sum.check_nft_amount()?;
+                asset.amount = sum.amount;
             }
             None => {
+                asset_to_add.check_nft_amount()?;
                 self.0.push(asset_to_add.clone());
             }
         }
@@ -272,15 +533,23 @@ impl AssetList {
 
     /// Generate a transfer messages for every asset in the list
     pub fn transfer_msgs<A: Into<String> + Clone>(&self, to: A) -> StdResult<Vec<CosmosMsg>> {
+        self.transfer_msgs_custom(to)
+    }
+
+    /// Like `transfer_msgs`, but for a contract using custom messages
+    pub fn transfer_msgs_custom<A: Into<String> + Clone, T>(
+        &self,
+        to: A,
+    ) -> StdResult<Vec<CosmosMsg<T>>> {
         self.0
             .iter()
-            .map(|asset| asset.transfer_msg(to.clone()))
-            .collect::<StdResult<Vec<CosmosMsg>>>()
+            .map(|asset| asset.transfer_msg_custom(to.clone()))
+            .collect::<StdResult<Vec<CosmosMsg<T>>>>()
     }
 
     /// Query balances for all assets in the list for the given address and
     /// return a new `AssetList`
-    pub fn query_balances(&self, querier: &QuerierWrapper, addr: &Addr) -> StdResult<AssetList> {
+    pub fn query_balances(&self, querier: &dyn AssetQuerier, addr: &Addr) -> StdResult<AssetList> {
         self.into_iter()
             .map(|asset| {
                 Ok(Asset::new(
@@ -289,14 +558,14 @@ impl AssetList {
                 ))
             })
             .collect::<StdResult<Vec<Asset>>>()
-            .map(Into::into)
+            .and_then(AssetList::try_from_assets)
     }
 
     /// Queries balances for all `AssetInfo` objects in the given vec for the
     /// given address and return a new `AssetList`
     pub fn query_asset_info_balances(
         asset_infos: Vec<AssetInfo>,
-        querier: &QuerierWrapper,
+        querier: &dyn AssetQuerier,
         addr: &Addr,
     ) -> StdResult<AssetList> {
         asset_infos
@@ -308,7 +577,66 @@ impl AssetList {
                 ))
             })
             .collect::<StdResult<Vec<Asset>>>()
-            .map(Into::into)
+            .and_then(AssetList::try_from_assets)
+    }
+
+    /// Return the total value of all assets in the list in base units of the
+    /// price source's quote asset
+    pub fn total_value(
+        &self,
+        querier: &dyn AssetQuerier,
+        source: &dyn PriceSource,
+    ) -> StdResult<Uint128> {
+        self.iter().try_fold(Uint128::zero(), |total, asset| {
+            Ok(total.checked_add(asset.value(querier, source)?)?)
+        })
+    }
+
+    /// Generate wasm event attributes describing the assets in the list
+    ///
+    /// The attributes are `{prefix}_count`, followed by the attributes of each
+    /// asset generated by `Asset::to_attributes` with prefix `{prefix}_{index}`.
+    /// The list can be read back with `AssetList::from_attributes`.
+    pub fn to_attributes(&self, prefix: &str) -> Vec<Attribute> {
+        let mut attributes = vec![Attribute::new(
+            attribute_key(prefix, "count"),
+            self.len().to_string(),
+        )];
+        for (i, asset) in self.iter().enumerate() {
+            attributes.extend(asset.to_attributes(&attribute_key(prefix, &i.to_string())));
+        }
+        attributes
+    }
+
+    /// Read an asset list back from attributes generated by
+    /// `AssetList::to_attributes` with the same prefix
+    pub fn from_attributes(prefix: &str, attributes: &[Attribute]) -> StdResult<Self> {
+        let count: usize = get_attribute(attributes, &attribute_key(prefix, "count"))?
+            .parse()
+            .map_err(|_| StdError::generic_err("invalid asset count"))?;
+        (0..count)
+            .map(|i| Asset::from_attributes(&attribute_key(prefix, &i.to_string()), attributes))
+            .collect::<StdResult<Vec<Asset>>>()
+            .and_then(AssetList::try_from_assets)
+    }
+
+    /// Generate an event of the given kind with the asset list embedded
+    ///
+    /// Additional attributes, e.g. the sender or recipient, can be added to the
+    /// returned event.
+    ///
+    /// **Usage:**
+    /// The following code emits a deposit event for the funds sent along with
+    /// a message.
+    ///
+    /// ```rust
+    /// let event = AssetList::from(info.funds)
+    ///     .to_event(AssetEventKind::Deposit)
+    ///     .add_attribute("sender", info.sender);
+    /// Ok(Response::new().add_event(event))
+    /// ```
+    pub fn to_event(&self, kind: AssetEventKind) -> Event {
+        Event::new(kind.as_str()).add_attributes(self.to_attributes(ASSET_EVENT_PREFIX))
     }
 }
 
@@ -330,10 +658,31 @@ mod test_helpers {
     }
 
     pub fn mock_list() -> AssetList {
-        AssetList::from(vec![
+        AssetList::try_from_assets(vec![
             Asset::native("uusd", 69420u128),
             Asset::new(mock_token(), 88888u128),
         ])
+        .unwrap()
+    }
+
+    #[cfg(feature = "terraswap")]
+    pub fn mock_terraswap_list() -> Vec<terraswap::asset::Asset> {
+        use cosmwasm_std::Uint128;
+
+        vec![
+            terraswap::asset::Asset {
+                info: terraswap::asset::AssetInfo::NativeToken {
+                    denom: "uusd".to_string(),
+                },
+                amount: Uint128::from(69420u128),
+            },
+            terraswap::asset::Asset {
+                info: terraswap::asset::AssetInfo::Token {
+                    contract_addr: "mock_token".to_string(),
+                },
+                amount: Uint128::from(88888u128),
+            },
+        ]
     }
 
     #[cfg(feature = "astroport")]
@@ -359,15 +708,17 @@ mod test_helpers {
 
 #[cfg(test)]
 mod tests {
+    use crate::testing::{contract_info_result, no_such_contract_result};
     use crate::{AssetInfoUnchecked, AssetUnchecked as AU};
 
     use super::super::asset::Asset;
     use super::test_helpers::{mock_list, mock_token, uluna, uusd};
     use super::*;
-    use cosmwasm_std::testing::MockApi;
+    use cosmwasm_std::testing::{MockApi, MockQuerier};
     use cosmwasm_std::{
-        to_binary, BankMsg, Coin, CosmosMsg, Decimal, OverflowError, OverflowOperation, Uint128,
-        WasmMsg,
+        attr, to_json_binary, BankMsg, Coin, ContractResult, CosmosMsg, CustomMsg, CustomQuery,
+        Decimal, OverflowError, OverflowOperation, QuerierWrapper, Response, SystemResult, Uint128,
+        WasmMsg, WasmQuery,
     };
     use cw20::Cw20ExecuteMsg;
 
@@ -386,7 +737,15 @@ mod tests {
     fn casting() {
         let api = MockApi::default();
 
+        // `MockApi` only accepts bech32 addresses in cosmwasm-std 2.x
+        #[cfg(feature = "cosmwasm-1")]
         let checked = mock_list();
+        #[cfg(not(feature = "cosmwasm-1"))]
+        let checked = AssetList::try_from_assets(vec![
+            Asset::native("uusd", 69420u128),
+            Asset::cw20(api.addr_make("mock_token"), 88888u128),
+        ])
+        .unwrap();
         let unchecked: AssetListUnchecked = checked.clone().into();
 
         assert_eq!(unchecked.check(&api).unwrap(), checked);
@@ -408,13 +767,14 @@ mod tests {
         let mut list = mock_list();
 
         let half = Decimal::from_ratio(1u128, 2u128);
-        list.apply(|asset: &mut Asset| asset.amount = asset.amount * half);
+        list.apply(|asset: &mut Asset| asset.amount = asset.amount.mul_floor(half));
         assert_eq!(
             list,
-            AssetList::from(vec![
+            AssetList::try_from_assets(vec![
                 Asset::native("uusd", 34710u128),
                 Asset::new(mock_token(), 44444u128)
             ])
+            .unwrap()
         );
     }
 
@@ -431,6 +791,34 @@ mod tests {
         assert_eq!(asset.amount, Uint128::new(69421));
     }
 
+    #[test]
+    fn adding_nfts() {
+        let mut list = mock_list();
+        let nft = Asset::cw721(Addr::unchecked("mock_nft"), "42");
+
+        list.add(&nft).unwrap();
+        assert_eq!(list.find(&nft.info), Some(&nft));
+
+        let err = list.add(&nft);
+        assert_eq!(
+            err,
+            Err(StdError::generic_err(
+                "cw721 asset amount must be 0 or 1: mock_nft:42:2"
+            ))
+        );
+        assert_eq!(list.find(&nft.info), Some(&nft));
+
+        let mut list = mock_list();
+        let err = list.add(&Asset::new(nft.info.clone(), 2u128));
+        assert_eq!(
+            err,
+            Err(StdError::generic_err(
+                "cw721 asset amount must be 0 or 1: mock_nft:42:2"
+            ))
+        );
+        assert_eq!(list, mock_list());
+    }
+
     #[test]
     fn adding_many() {
         let mut list = mock_list();
@@ -460,6 +848,7 @@ mod tests {
         assert_eq!(asset.amount, Uint128::new(76543));
 
         let err = list.deduct(&Asset::new(mock_token(), 99999u128));
+        #[cfg(feature = "cosmwasm-1")]
         assert_eq!(
             err,
             Err(StdError::overflow(OverflowError::new(
@@ -468,6 +857,14 @@ mod tests {
                 Uint128::new(99999)
             )))
         );
+        // the operands are no longer part of the error in cosmwasm-std 2.x
+        #[cfg(not(feature = "cosmwasm-1"))]
+        assert_eq!(
+            err,
+            Err(StdError::overflow(OverflowError::new(
+                OverflowOperation::Sub
+            )))
+        );
     }
 
     #[test]
@@ -486,11 +883,11 @@ mod tests {
             vec![
                 CosmosMsg::Bank(BankMsg::Send {
                     to_address: String::from("alice"),
-                    amount: vec![Coin::new(69420, "uusd")]
+                    amount: vec![Coin::new(69420u128, "uusd")]
                 }),
                 CosmosMsg::Wasm(WasmMsg::Execute {
                     contract_addr: String::from("mock_token"),
-                    msg: to_binary(&Cw20ExecuteMsg::Transfer {
+                    msg: to_json_binary(&Cw20ExecuteMsg::Transfer {
                         recipient: String::from("alice"),
                         amount: Uint128::new(88888)
                     })
@@ -501,6 +898,44 @@ mod tests {
         );
     }
 
+    #[derive(Serialize, Deserialize, Clone, Debug, PartialEq, JsonSchema)]
+    struct MockCustomMsg {}
+
+    impl CustomMsg for MockCustomMsg {}
+
+    #[derive(Serialize, Deserialize, Clone, Debug, PartialEq, JsonSchema)]
+    struct MockCustomQuery {}
+
+    impl CustomQuery for MockCustomQuery {}
+
+    #[test]
+    fn custom_messages_and_queries() {
+        let list = mock_list();
+        let res: Response<MockCustomMsg> = Response::new()
+            .add_messages(list.transfer_msgs_custom("alice").unwrap())
+            .add_message(list.to_vec()[0].transfer_msg_custom("bob").unwrap());
+        assert_eq!(res.messages.len(), 3);
+
+        let querier =
+            MockQuerier::<MockCustomQuery>::new(&[("alice", &[Coin::new(12345u128, "uusd")])]);
+        let querier = QuerierWrapper::<MockCustomQuery>::new(&querier);
+        let balances = AssetList::try_from_assets(vec![Asset::native("uusd", 1u128)])
+            .unwrap()
+            .query_balances(&querier, &Addr::unchecked("alice"))
+            .unwrap();
+        assert_eq!(
+            balances,
+            AssetList::try_from_assets(vec![Asset::native("uusd", 12345u128)]).unwrap()
+        );
+
+        let source =
+            crate::FixedPriceSource::new(vec![(AssetInfo::native("uusd"), Decimal::one())]);
+        assert_eq!(
+            balances.total_value(&querier, &source).unwrap(),
+            Uint128::new(12345)
+        );
+    }
+
     #[test]
     fn unchecked_from_vec() {
         let asset1 = AssetUnchecked {
@@ -515,17 +950,18 @@ mod tests {
 
         let list: AssetListUnchecked = vec![asset1.clone(), asset2.clone()].into();
 
-        let expected = AssetList::from(vec![
+        let expected = AssetList::try_from_assets(vec![
             asset1.check(&api).unwrap(),
             asset2.check(&api).unwrap(),
-        ]);
+        ])
+        .unwrap();
 
         assert_eq!(list.check(&api).unwrap(), expected);
     }
 
     #[test]
     fn generic_from() {
-        let coins = vec![Coin::new(1234, "coin1"), Coin::new(5678, "coin2")];
+        let coins = vec![Coin::new(1234u128, "coin1"), Coin::new(5678u128, "coin2")];
 
         let list: AssetList = coins.into();
 
@@ -543,6 +979,11 @@ mod tests {
         assert_eq!(list, unchecked.check(&MockApi::default()).unwrap());
     }
 
+    /// A CW20 address accepted by the cosmwasm-std 2.x `MockApi`, i.e.
+    /// `MockApi::addr_make("mock_token")`
+    #[cfg(not(feature = "cosmwasm-1"))]
+    const MOCK_CW20: &str = "cosmwasm1y9lhwty6am8fehg6f3ft6qzlxqhphwgxysn5y0zekzc0npy5sdhsqenyp8";
+
     #[test_case(vec![], vec![]; "empty")]
     #[test_case(vec![AU::native("coin1", 12345u128), AU::native("coin2", 67890u128)],
                 vec![Asset::native("coin1", 12345u128), Asset::native("coin2", 67890u128)];
@@ -550,9 +991,12 @@ mod tests {
     #[test_case(vec![AU::native("coin1", 12345u128), AU::native("coin1", 67890u128)],
                 vec![Asset::native("coin1", 80235u128)] ;
                 "duplicates")]
-    #[test_case(vec![AU::native("coin1", 12345u128), AU::cw20("coin2", 67890u128)],
+    #[cfg_attr(feature = "cosmwasm-1", test_case(vec![AU::native("coin1", 12345u128), AU::cw20("coin2", 67890u128)],
                 vec![Asset::native("coin1", 12345u128), Asset::cw20(Addr::unchecked("coin2"), 67890u128)];
-                "cw20 valid mock address")]
+                "cw20 valid mock address"))]
+    #[cfg_attr(not(feature = "cosmwasm-1"), test_case(vec![AU::native("coin1", 12345u128), AU::cw20(MOCK_CW20, 67890u128)],
+                vec![Asset::native("coin1", 12345u128), Asset::cw20(Addr::unchecked(MOCK_CW20), 67890u128)];
+                "cw20 valid mock address"))]
     #[test_case(vec![AU::native("coin1", 12345u128), AU::cw20("co", 67890u128)],
                 vec![Asset::native("coin1", 12345u128), Asset::cw20(Addr::unchecked("co"), 67890u128)]
                 => matches Err(_) ;
@@ -562,12 +1006,175 @@ mod tests {
 
         assert_eq!(
             unchecked.check(&MockApi::default())?,
-            AssetList::from(expected)
+            AssetList::try_from_assets(expected).unwrap()
         );
 
         Ok(())
     }
 
+    #[test_case(vec![AU::native("coin1", 12345u128), AU::native("coin2", 67890u128)]
+                => Ok(AssetList::try_from_assets(vec![Asset::native("coin1", 12345u128), Asset::native("coin2", 67890u128)]).unwrap());
+                "native")]
+    #[test_case(vec![AU::native("coin1", 12345u128), AU::native("coin1", 67890u128)]
+                => Err(StdError::generic_err("duplicate asset: coin1"));
+                "duplicates")]
+    #[test_case(vec![AU::native("coin1", 12345u128), AU::cw20("coin2", 0u128)]
+                => Err(StdError::generic_err("zero amount: coin2"));
+                "zero amount")]
+    #[test_case(vec![AU::native("coin1", 12345u128), AU::cw20("co", 67890u128)]
+                => matches Err(_) ;
+                "cw20 invalid mock address")]
+    fn check_strict(unchecked: Vec<AssetUnchecked>) -> StdResult<AssetList> {
+        AssetListUnchecked::from(unchecked).check_strict(&MockApi::default())
+    }
+
+    #[test]
+    #[cfg(feature = "cosmwasm-1")]
+    fn check_with_allowlist() {
+        let api = MockApi::default();
+        let unchecked: AssetListUnchecked = mock_list().into();
+
+        let list = unchecked
+            .check_with_allowlist(&api, &[uusd(), mock_token()])
+            .unwrap();
+        assert_eq!(list, mock_list());
+
+        let err = unchecked.check_with_allowlist(&api, &[uusd(), uluna()]);
+        assert_eq!(
+            err,
+            Err(StdError::generic_err("asset not allowed: mock_token"))
+        );
+    }
+
+    #[test]
+    #[cfg(not(feature = "cosmwasm-1"))]
+    fn check_with_allowlist_bech32() {
+        let api = MockApi::default();
+        let token = AssetInfo::cw20(api.addr_make("mock_token"));
+        let checked = AssetList::try_from_assets(vec![
+            Asset::native("uusd", 69420u128),
+            Asset::new(token.clone(), 88888u128),
+        ])
+        .unwrap();
+        let unchecked: AssetListUnchecked = checked.clone().into();
+
+        let list = unchecked
+            .check_with_allowlist(&api, &[uusd(), token.clone()])
+            .unwrap();
+        assert_eq!(list, checked);
+
+        let err = unchecked.check_with_allowlist(&api, &[uusd(), uluna()]);
+        assert_eq!(
+            err,
+            Err(StdError::generic_err(format!(
+                "asset not allowed: {}",
+                token
+            )))
+        );
+    }
+
+    #[test]
+    fn checking_with_querier() {
+        let api = MockApi::default();
+        let token = api.addr_make("mock_token");
+        let wallet = api.addr_make("alice");
+
+        let mut querier: MockQuerier = MockQuerier::new(&[]);
+        let token_addr = token.to_string();
+        querier.update_wasm(move |query| match query {
+            WasmQuery::ContractInfo { contract_addr } if *contract_addr == token_addr => {
+                contract_info_result()
+            }
+            WasmQuery::Smart { .. } => SystemResult::Ok(ContractResult::Ok(
+                to_json_binary(&cw20::TokenInfoResponse {
+                    name: "Mock Token".to_string(),
+                    symbol: "MOCK".to_string(),
+                    decimals: 6,
+                    total_supply: Uint128::new(1_000_000),
+                })
+                .unwrap(),
+            )),
+            WasmQuery::ContractInfo { contract_addr } => no_such_contract_result(contract_addr),
+            _ => panic!("[mock]: unsupported wasm query {:?}", query),
+        });
+        let querier: QuerierWrapper = QuerierWrapper::new(&querier);
+
+        let unchecked = AssetListUnchecked::from(vec![
+            AU::native("uusd", 69420u128),
+            AU::cw20(token.as_str(), 88888u128),
+            AU::cw20(token.as_str(), 11112u128),
+        ]);
+        assert_eq!(
+            unchecked.check_with_querier(&api, &querier).unwrap(),
+            AssetList::try_from_assets(vec![
+                Asset::native("uusd", 69420u128),
+                Asset::cw20(token, 100000u128),
+            ])
+            .unwrap()
+        );
+
+        let unchecked = AssetListUnchecked::from(vec![
+            AU::native("uusd", 69420u128),
+            AU::cw20(wallet.as_str(), 88888u128),
+        ]);
+        assert_eq!(
+            unchecked.check_with_querier(&api, &querier),
+            Err(AssetError::NotAContract {
+                address: wallet.to_string()
+            })
+        );
+    }
+
+    #[test]
+    fn converting_bech32_prefix() {
+        let unchecked = AssetListUnchecked::from(vec![
+            AU::native("uosmo", 69420u128),
+            AU::cw20(
+                "osmo1h34lmpywh4upnjdg90cjf4j70aee6z8qqfspugamjp42e4q28kqsqgyqyr",
+                88888u128,
+            ),
+        ]);
+        assert_eq!(
+            unchecked.with_bech32_prefix("neutron").unwrap(),
+            AssetListUnchecked::from(vec![
+                AU::native("uosmo", 69420u128),
+                AU::cw20(
+                    "neutron1h34lmpywh4upnjdg90cjf4j70aee6z8qqfspugamjp42e4q28kqs56dudx",
+                    88888u128,
+                ),
+            ])
+        );
+
+        let mut unchecked = unchecked;
+        unchecked.push(AU::cw20("mock_token", 1u128));
+        assert!(matches!(
+            unchecked.with_bech32_prefix("neutron"),
+            Err(AssetError::InvalidBech32Address { .. })
+        ));
+    }
+
+    #[test]
+    fn pushing_unchecked() {
+        let mut list = AssetListUnchecked::new();
+        assert_eq!(list.len(), 0);
+
+        list.push(AU::native("uusd", 69420u128))
+            .push(AU::cw20("mock_token", 88888u128))
+            .push(AU::native("uusd", 0u128));
+        assert_eq!(list.len(), 3);
+
+        let mut iter = list.iter();
+        assert_eq!(iter.next(), Some(&AU::native("uusd", 69420u128)));
+        assert_eq!(iter.next(), Some(&AU::cw20("mock_token", 88888u128)));
+        assert_eq!(iter.next(), Some(&AU::native("uusd", 0u128)));
+        assert_eq!(iter.next(), None);
+
+        assert_eq!(
+            list.to_string(),
+            String::from("uusd:69420,mock_token:88888,uusd:0")
+        );
+    }
+
     #[test]
     fn into_iter() {
         let list = mock_list();
@@ -606,7 +1213,7 @@ mod tests {
     #[test]
     fn get_native_coins() {
         let list = mock_list();
-        assert_eq!(list.get_native_coins(), vec![Coin::new(69420, "uusd")]);
+        assert_eq!(list.get_native_coins(), vec![Coin::new(69420u128, "uusd")]);
     }
 
     #[test]
@@ -631,8 +1238,117 @@ mod tests {
 
         let list = mock_list();
 
-        let vec_asset_info = Vec::<astroport::asset::Asset>::from(list);
+        let vec_asset_info = Vec::<astroport::asset::Asset>::try_from(list).unwrap();
 
         assert_eq!(vec_asset_info, mock_astro_list());
     }
+
+    #[test]
+    #[cfg(feature = "astroport")]
+    fn from_vec_astro_asset_for_assetlist() {
+        use crate::asset_list::test_helpers::mock_astro_list;
+
+        assert_eq!(AssetList::from(mock_astro_list()), mock_list());
+    }
+
+    #[test]
+    #[cfg(feature = "astroport")]
+    fn from_vec_astro_asset_for_assetlistunchecked() {
+        use crate::asset_list::test_helpers::mock_astro_list;
+
+        let unchecked = AssetListUnchecked::from(mock_astro_list());
+        assert_eq!(unchecked, mock_list().into());
+    }
+
+    #[test]
+    #[cfg(feature = "terraswap")]
+    fn terraswap_list_conversions() {
+        use crate::asset_list::test_helpers::mock_terraswap_list;
+
+        let vec_asset = Vec::<terraswap::asset::Asset>::try_from(mock_list()).unwrap();
+        assert_eq!(vec_asset, mock_terraswap_list());
+
+        let unchecked = AssetListUnchecked::from(mock_terraswap_list());
+        assert_eq!(unchecked.check(&MockApi::default()).unwrap(), mock_list());
+        assert_eq!(
+            Vec::<terraswap::asset::Asset>::try_from(unchecked).unwrap(),
+            mock_terraswap_list()
+        );
+    }
+
+    #[test]
+    #[cfg(feature = "cw-asset-compat")]
+    fn cw_asset_list_conversions() {
+        use cosmwasm_std::{from_json, to_json_vec};
+
+        let upstream: cw_asset::AssetList = mock_list().try_into().unwrap();
+        assert_eq!(
+            AssetList::try_from_cw_asset_list(&upstream).unwrap(),
+            mock_list()
+        );
+
+        let json = to_json_vec(&mock_list()).unwrap();
+        assert_eq!(json, to_json_vec(&upstream).unwrap());
+        assert_eq!(from_json::<cw_asset::AssetList>(&json).unwrap(), upstream);
+
+        let upstream = cw_asset::AssetList::from(vec![
+            cw_asset::Asset::native("uusd", 69420u128),
+            cw_asset::Asset::cw1155(Addr::unchecked("mock_nft"), "1", 1u128),
+        ]);
+        assert_eq!(
+            AssetList::try_from_cw_asset_list(&upstream),
+            Err(AssetError::UnsupportedCw1155 {
+                contract_addr: "mock_nft".to_string(),
+                token_id: "1".to_string(),
+            })
+        );
+    }
+
+    #[test]
+    fn asset_list_attributes() {
+        let attributes = mock_list().to_attributes("asset");
+        assert_eq!(
+            attributes,
+            vec![
+                attr("asset_count", "2"),
+                attr("asset_0_kind", "native"),
+                attr("asset_0_denom", "uusd"),
+                attr("asset_0_amount", "69420"),
+                attr("asset_1_kind", "cw20"),
+                attr("asset_1_contract", "mock_token"),
+                attr("asset_1_amount", "88888"),
+            ]
+        );
+        assert_eq!(
+            AssetList::from_attributes("asset", &attributes).unwrap(),
+            mock_list()
+        );
+        assert_eq!(
+            AssetList::from_attributes("asset", &attributes[..4]),
+            Err(StdError::generic_err("attribute not found: asset_1_kind"))
+        );
+    }
+
+    #[test]
+    fn merging_nfts() {
+        let nft = Asset::cw721(Addr::unchecked("mock_nft"), "1");
+        let err = AssetList::try_from_assets(vec![nft.clone(), nft.clone()]);
+        assert_eq!(
+            err,
+            Err(StdError::generic_err(
+                "cw721 asset amount must be 0 or 1: mock_nft:1:2"
+            ))
+        );
+
+        let mut attributes = AssetList::try_from_assets(vec![nft])
+            .unwrap()
+            .to_attributes("asset");
+        attributes[4] = attr("asset_0_amount", "2");
+        assert_eq!(
+            AssetList::from_attributes("asset", &attributes),
+            Err(StdError::generic_err(
+                "cw721 asset amount must be 0 or 1: mock_nft:1:2"
+            ))
+        );
+    }
 }
@@ -0,0 +1,230 @@
+use cosmwasm_std::{Order, StdError, StdResult, Storage};
+use cw_storage_plus::Bound;
+
+use serde::de::DeserializeOwned;
+use serde::Serialize;
+
+use super::asset_info::{AssetInfo, AssetInfoKey};
+use super::asset_list::AssetList;
+use super::compat::Map;
+
+const DEFAULT_LIMIT: u32 = 10;
+const MAX_LIMIT: u32 = 30;
+
+/// An allowlist of assets stored in contract state, together with a config of
+/// type `C` for each allowed asset
+///
+/// **Usage:**
+/// The following code declares a registry in contract state and only accepts
+/// deposits of registered assets.
+///
+/// ```rust
+/// const ASSETS: AssetRegistry<AssetConfig> = AssetRegistry::new("assets");
+///
+/// ASSETS.register(deps.storage, &AssetInfo::native("uosmo"), &config)?;
+/// ASSETS.assert_allowed(deps.storage, &deposit)?;
+/// ```
+pub struct AssetRegistry<C> {
+    configs: Map<'static, AssetInfoKey, C>,
+}
+
+impl<C> AssetRegistry<C>
+where
+    C: Serialize + DeserializeOwned,
+{
+    /// Create a new registry stored under the given storage namespace
+    pub const fn new(namespace: &'static str) -> Self {
+        Self {
+            configs: Map::new(namespace),
+        }
+    }
+
+    /// Add an asset to the registry, or replace the config of an asset that is
+    /// already registered
+    pub fn register(
+        &self,
+        storage: &mut dyn Storage,
+        info: &AssetInfo,
+        config: &C,
+    ) -> StdResult<()> {
+        self.configs.save(storage, info.into(), config)
+    }
+
+    /// Remove an asset from the registry
+    ///
+    /// Returns an error if the asset is not registered.
+    pub fn deregister(&self, storage: &mut dyn Storage, info: &AssetInfo) -> StdResult<()> {
+        if !self.is_allowed(storage, info) {
+            return Err(StdError::generic_err(format!("not registered: {}", info)));
+        }
+        self.configs.remove(storage, info.into());
+        Ok(())
+    }
+
+    /// Return whether the asset is registered
+    pub fn is_allowed(&self, storage: &dyn Storage, info: &AssetInfo) -> bool {
+        self.configs.has(storage, info.into())
+    }
+
+    /// Return the config of a registered asset
+    ///
+    /// Returns an error if the asset is not registered.
+    pub fn config(&self, storage: &dyn Storage, info: &AssetInfo) -> StdResult<C> {
+        self.configs
+            .may_load(storage, info.into())?
+            .ok_or_else(|| StdError::generic_err(format!("not registered: {}", info)))
+    }
+
+    /// Return an error if any asset in the list is not registered
+    pub fn assert_allowed(&self, storage: &dyn Storage, assets: &AssetList) -> StdResult<()> {
+        for asset in assets {
+            if !self.is_allowed(storage, &asset.info) {
+                return Err(StdError::generic_err(format!(
+                    "asset not allowed: {}",
+                    asset.info
+                )));
+            }
+        }
+        Ok(())
+    }
+
+    /// Return a page of registered assets and their configs, ordered by
+    /// `AssetInfoKey`
+    ///
+    /// `limit` defaults to 10 and is capped at 30.
+    pub fn list(
+        &self,
+        storage: &dyn Storage,
+        start_after: Option<&AssetInfo>,
+        limit: Option<u32>,
+    ) -> StdResult<Vec<(AssetInfo, C)>> {
+        let start = start_after.map(|info| Bound::exclusive(AssetInfoKey::from(info)));
+        let limit = limit.unwrap_or(DEFAULT_LIMIT).min(MAX_LIMIT) as usize;
+
+        self.configs
+            .range(storage, start, None, Order::Ascending)
+            .take(limit)
+            .map(|item| item.map(|(key, config)| (key.into(), config)))
+            .collect()
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::Asset;
+    use cosmwasm_std::testing::MockStorage;
+    use cosmwasm_std::{Addr, Uint128};
+    use serde::Deserialize;
+
+    #[derive(Serialize, Deserialize, Clone, Debug, PartialEq)]
+    struct MockConfig {
+        max_deposit: Uint128,
+    }
+
+    const REGISTRY: AssetRegistry<MockConfig> = AssetRegistry::new("registry");
+
+    fn config(max_deposit: u128) -> MockConfig {
+        MockConfig {
+            max_deposit: Uint128::new(max_deposit),
+        }
+    }
+
+    fn mock_token() -> AssetInfo {
+        AssetInfo::cw20(Addr::unchecked("mock_token"))
+    }
+
+    #[test]
+    fn registering() {
+        let mut storage = MockStorage::default();
+        let uusd = AssetInfo::native("uusd");
+
+        assert!(!REGISTRY.is_allowed(&storage, &uusd));
+        assert_eq!(
+            REGISTRY.config(&storage, &uusd),
+            Err(StdError::generic_err("not registered: uusd"))
+        );
+
+        REGISTRY
+            .register(&mut storage, &uusd, &config(100))
+            .unwrap();
+        assert!(REGISTRY.is_allowed(&storage, &uusd));
+        assert_eq!(REGISTRY.config(&storage, &uusd).unwrap(), config(100));
+
+        REGISTRY
+            .register(&mut storage, &uusd, &config(200))
+            .unwrap();
+        assert_eq!(REGISTRY.config(&storage, &uusd).unwrap(), config(200));
+    }
+
+    #[test]
+    fn deregistering() {
+        let mut storage = MockStorage::default();
+        let uusd = AssetInfo::native("uusd");
+
+        REGISTRY
+            .register(&mut storage, &uusd, &config(100))
+            .unwrap();
+        REGISTRY.deregister(&mut storage, &uusd).unwrap();
+        assert!(!REGISTRY.is_allowed(&storage, &uusd));
+
+        let err = REGISTRY.deregister(&mut storage, &uusd);
+        assert_eq!(err, Err(StdError::generic_err("not registered: uusd")));
+    }
+
+    #[test]
+    fn asserting_allowed() {
+        let mut storage = MockStorage::default();
+        REGISTRY
+            .register(&mut storage, &AssetInfo::native("uusd"), &config(100))
+            .unwrap();
+
+        let list = AssetList::try_from_assets(vec![Asset::native("uusd", 69420u128)]).unwrap();
+        REGISTRY.assert_allowed(&storage, &list).unwrap();
+
+        let list = AssetList::try_from_assets(vec![
+            Asset::native("uusd", 69420u128),
+            Asset::new(mock_token(), 88888u128),
+        ])
+        .unwrap();
+        let err = REGISTRY.assert_allowed(&storage, &list);
+        assert_eq!(
+            err,
+            Err(StdError::generic_err("asset not allowed: mock_token"))
+        );
+    }
+
+    #[test]
+    fn listing() {
+        let mut storage = MockStorage::default();
+        let infos = [
+            AssetInfo::native("uatom"),
+            AssetInfo::native("uosmo"),
+            AssetInfo::native("uusd"),
+            mock_token(),
+        ];
+        for (i, info) in infos.iter().enumerate() {
+            REGISTRY
+                .register(&mut storage, info, &config(i as u128))
+                .unwrap();
+        }
+
+        let all = REGISTRY.list(&storage, None, None).unwrap();
+        // CW20 keys are prefixed with `u8::MIN`, so they come before natives
+        assert_eq!(
+            all,
+            vec![
+                (mock_token(), config(3)),
+                (AssetInfo::native("uatom"), config(0)),
+                (AssetInfo::native("uosmo"), config(1)),
+                (AssetInfo::native("uusd"), config(2)),
+            ]
+        );
+
+        let page = REGISTRY.list(&storage, None, Some(2)).unwrap();
+        assert_eq!(page, all[..2].to_vec());
+
+        let page = REGISTRY.list(&storage, Some(&page[1].0), Some(2)).unwrap();
+        assert_eq!(page, all[2..].to_vec());
+    }
+}
@@ -0,0 +1,373 @@
+use std::convert::TryInto;
+
+use astroport::asset::PairInfo;
+use astroport::pair::{PoolResponse, QueryMsg as PairQueryMsg};
+use astroport::router::{
+    Cw20HookMsg as RouterCw20HookMsg, ExecuteMsg as RouterExecuteMsg, QueryMsg as RouterQueryMsg,
+    SimulateSwapOperationsResponse, SwapOperation,
+};
+use cosmwasm_std::{
+    from_json, to_json_binary, Addr, CosmosMsg, CustomQuery, Decimal, QuerierWrapper, StdError,
+    StdResult, Uint128, WasmMsg,
+};
+
+use crate::swap_router::swaps_to_make;
+use crate::{AssetInfo, AssetList, AssetQuerier, SwapRouter};
+
+/// Convert the asset infos of an Astroport pair into `[AssetInfo; 2]`
+pub fn from_astro_asset_infos(infos: [astroport::asset::AssetInfo; 2]) -> [AssetInfo; 2] {
+    infos.map(Into::into)
+}
+
+/// Convert `[AssetInfo; 2]` into the asset infos of an Astroport pair, e.g. for
+/// querying the Astroport factory
+///
+/// Returns an error if either asset is a CW721 NFT.
+pub fn to_astro_asset_infos(infos: [AssetInfo; 2]) -> StdResult<[astroport::asset::AssetInfo; 2]> {
+    let [first, second] = infos;
+    Ok([first.try_into()?, second.try_into()?])
+}
+
+/// Return the asset infos of an Astroport pair
+///
+/// Returns an error if the pair does not contain exactly 2 assets.
+pub fn pair_asset_infos(pair_info: &PairInfo) -> StdResult<[AssetInfo; 2]> {
+    let infos: [astroport::asset::AssetInfo; 2] = pair_info
+        .asset_infos
+        .clone()
+        .try_into()
+        .map_err(|infos: Vec<_>| {
+            StdError::generic_err(format!(
+                "PairInfo must contain exactly 2 assets, but it contains {}",
+                infos.len()
+            ))
+        })?;
+    Ok(from_astro_asset_infos(infos))
+}
+
+/// Query the reserves of an Astroport pair by reading the balances of the
+/// pair's assets held by the pair contract
+pub fn query_pair_pool<C: CustomQuery>(
+    querier: &QuerierWrapper<C>,
+    pair_info: &PairInfo,
+) -> StdResult<AssetList> {
+    AssetList::query_asset_info_balances(
+        pair_info
+            .asset_infos
+            .iter()
+            .cloned()
+            .map(Into::into)
+            .collect(),
+        querier,
+        &pair_info.contract_addr,
+    )
+}
+
+/// Query the reserves of an Astroport pair using the pair's `Pool` query
+pub fn query_pool_assets<C: CustomQuery, A: Into<String>>(
+    querier: &QuerierWrapper<C>,
+    pair_addr: A,
+) -> StdResult<AssetList> {
+    let res: PoolResponse = querier.query_wasm_smart(pair_addr, &PairQueryMsg::Pool {})?;
+    Ok(res.assets.into())
+}
+
+/// A swap router using the Astroport router contract, swapping each asset
+/// directly through the Astroport pair of the asset and the ask asset
+#[derive(Clone, Debug, PartialEq)]
+pub struct AstroportRouter {
+    pub router: Addr,
+    /// The maximum spread of each swap, or Astroport's default if `None`
+    pub max_spread: Option<Decimal>,
+}
+
+impl AstroportRouter {
+    /// Create a new router for the given Astroport router contract
+    pub fn new(router: Addr, max_spread: Option<Decimal>) -> Self {
+        Self { router, max_spread }
+    }
+}
+
+fn swap_operations(offer: &AssetInfo, ask: &AssetInfo) -> StdResult<Vec<SwapOperation>> {
+    Ok(vec![SwapOperation::AstroSwap {
+        offer_asset_info: offer.clone().try_into()?,
+        ask_asset_info: ask.clone().try_into()?,
+    }])
+}
+
+impl SwapRouter for AstroportRouter {
+    fn swap_msgs(
+        &self,
+        offer: &AssetList,
+        ask: &AssetInfo,
+        min_outs: &[(AssetInfo, Uint128)],
+    ) -> StdResult<Vec<CosmosMsg>> {
+        swaps_to_make(offer, ask, min_outs)?
+            .into_iter()
+            .map(|(asset, min_out)| {
+                let minimum_receive = Some(min_out).filter(|amount| !amount.is_zero());
+                match &asset.info {
+                    AssetInfo::Native(_) => Ok(CosmosMsg::Wasm(WasmMsg::Execute {
+                        contract_addr: self.router.to_string(),
+                        msg: to_json_binary(&RouterExecuteMsg::ExecuteSwapOperations {
+                            operations: swap_operations(&asset.info, ask)?,
+                            minimum_receive,
+                            to: None,
+                            max_spread: self.max_spread,
+                        })?,
+                        funds: vec![asset.try_into()?],
+                    })),
+                    AssetInfo::Cw20(_) => asset.send_msg(
+                        &self.router,
+                        to_json_binary(&RouterCw20HookMsg::ExecuteSwapOperations {
+                            operations: swap_operations(&asset.info, ask)?,
+                            minimum_receive,
+                            to: None,
+                            max_spread: self.max_spread,
+                        })?,
+                    ),
+                    AssetInfo::Cw721(..) => {
+                        Err(StdError::generic_err("cw721 tokens can not be swapped"))
+                    }
+                }
+            })
+            .collect()
+    }
+
+    fn simulate_swap(
+        &self,
+        querier: &dyn AssetQuerier,
+        offer: &AssetList,
+        ask: &AssetInfo,
+    ) -> StdResult<Uint128> {
+        swaps_to_make(offer, ask, &[])?.into_iter().try_fold(
+            Uint128::zero(),
+            |total, (asset, _)| {
+                let msg = to_json_binary(&RouterQueryMsg::SimulateSwapOperations {
+                    offer_amount: asset.amount,
+                    operations: swap_operations(&asset.info, ask)?,
+                })?;
+                let res: SimulateSwapOperationsResponse =
+                    from_json(querier.query_contract(self.router.as_str(), &msg)?)?;
+                Ok(total.checked_add(res.amount)?)
+            },
+        )
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::Asset;
+    use astroport::factory::PairType;
+    use cosmwasm_std::testing::MockQuerier;
+    use cosmwasm_std::{
+        from_json, to_json_binary, Addr, Coin, ContractResult, SystemResult, Uint128, WasmQuery,
+    };
+    use cw20::{BalanceResponse, Cw20QueryMsg};
+
+    fn mock_token() -> AssetInfo {
+        AssetInfo::cw20(Addr::unchecked("mock_token"))
+    }
+
+    fn astro_infos() -> [astroport::asset::AssetInfo; 2] {
+        [
+            astroport::asset::AssetInfo::NativeToken {
+                denom: "uusd".to_string(),
+            },
+            astroport::asset::AssetInfo::Token {
+                contract_addr: Addr::unchecked("mock_token"),
+            },
+        ]
+    }
+
+    fn mock_pair_info() -> PairInfo {
+        PairInfo {
+            asset_infos: astro_infos().to_vec(),
+            contract_addr: Addr::unchecked("mock_pair"),
+            liquidity_token: Addr::unchecked("mock_lp_token"),
+            pair_type: PairType::Xyk {},
+        }
+    }
+
+    fn mock_querier() -> MockQuerier {
+        let mut querier = MockQuerier::new(&[("mock_pair", &[Coin::new(69420u128, "uusd")])]);
+        querier.update_wasm(|query| {
+            let (contract_addr, msg) = match query {
+                WasmQuery::Smart { contract_addr, msg } => (contract_addr, msg),
+                _ => panic!("[mock]: unsupported wasm query {:?}", query),
+            };
+            let res = match contract_addr.as_str() {
+                "mock_token" => match from_json(msg).unwrap() {
+                    Cw20QueryMsg::Balance { address } if address == "mock_pair" => {
+                        to_json_binary(&BalanceResponse {
+                            balance: Uint128::new(88888),
+                        })
+                    }
+                    query => panic!("[mock]: unsupported cw20 query {:?}", query),
+                },
+                "mock_pair" => match from_json(msg).unwrap() {
+                    PairQueryMsg::Pool {} => to_json_binary(&PoolResponse {
+                        assets: vec![
+                            Asset::native("uusd", 69420u128).try_into().unwrap(),
+                            Asset::new(mock_token(), 88888u128).try_into().unwrap(),
+                        ],
+                        total_share: Uint128::new(12345),
+                    }),
+                    _ => panic!("[mock]: unsupported pair query"),
+                },
+                "mock_router" => match from_json(msg).unwrap() {
+                    RouterQueryMsg::SimulateSwapOperations { offer_amount, .. } => {
+                        to_json_binary(&SimulateSwapOperationsResponse {
+                            amount: offer_amount.multiply_ratio(1u128, 2u128),
+                        })
+                    }
+                    _ => panic!("[mock]: unsupported router query"),
+                },
+                _ => panic!("[mock]: unknown contract {}", contract_addr),
+            };
+            SystemResult::Ok(ContractResult::Ok(res.unwrap()))
+        });
+        querier
+    }
+
+    fn mock_pool() -> AssetList {
+        AssetList::try_from_assets(vec![
+            Asset::native("uusd", 69420u128),
+            Asset::new(mock_token(), 88888u128),
+        ])
+        .unwrap()
+    }
+
+    #[test]
+    fn converting_asset_infos() {
+        let infos = from_astro_asset_infos(astro_infos());
+        assert_eq!(infos, [AssetInfo::native("uusd"), mock_token()]);
+        assert_eq!(to_astro_asset_infos(infos).unwrap(), astro_infos());
+    }
+
+    #[test]
+    fn reading_pair_asset_infos() {
+        let pair_info = mock_pair_info();
+        assert_eq!(
+            pair_asset_infos(&pair_info).unwrap(),
+            [AssetInfo::native("uusd"), mock_token()]
+        );
+
+        let mut pair_info = mock_pair_info();
+        pair_info.asset_infos.pop();
+        assert_eq!(
+            pair_asset_infos(&pair_info),
+            Err(StdError::generic_err(
+                "PairInfo must contain exactly 2 assets, but it contains 1"
+            ))
+        );
+    }
+
+    #[test]
+    fn querying_pair_pool() {
+        let querier = mock_querier();
+        let querier: QuerierWrapper = QuerierWrapper::new(&querier);
+
+        let pool = query_pair_pool(&querier, &mock_pair_info()).unwrap();
+        assert_eq!(pool, mock_pool());
+    }
+
+    #[test]
+    fn querying_pool_assets() {
+        let querier = mock_querier();
+        let querier: QuerierWrapper = QuerierWrapper::new(&querier);
+
+        let pool = query_pool_assets(&querier, "mock_pair").unwrap();
+        assert_eq!(pool, mock_pool());
+    }
+
+    #[test]
+    fn swapping_through_router() {
+        let router = AstroportRouter::new(Addr::unchecked("mock_router"), None);
+        let astro_uusd = astroport::asset::AssetInfo::NativeToken {
+            denom: "uusd".to_string(),
+        };
+
+        let offer = AssetList::try_from_assets(vec![Asset::new(mock_token(), 88888u128)]).unwrap();
+        let msgs = router
+            .swap_msgs(
+                &offer,
+                &AssetInfo::native("uusd"),
+                &[(mock_token(), Uint128::new(40000))],
+            )
+            .unwrap();
+        assert_eq!(
+            msgs,
+            vec![Asset::new(mock_token(), 88888u128)
+                .send_msg(
+                    "mock_router",
+                    to_json_binary(&RouterCw20HookMsg::ExecuteSwapOperations {
+                        operations: vec![SwapOperation::AstroSwap {
+                            offer_asset_info: astroport::asset::AssetInfo::Token {
+                                contract_addr: Addr::unchecked("mock_token"),
+                            },
+                            ask_asset_info: astro_uusd.clone(),
+                        }],
+                        minimum_receive: Some(Uint128::new(40000)),
+                        to: None,
+                        max_spread: None,
+                    })
+                    .unwrap(),
+                )
+                .unwrap()]
+        );
+
+        let offer = AssetList::try_from_assets(vec![Asset::native("uluna", 100u128)]).unwrap();
+        let msgs = router
+            .swap_msgs(&offer, &AssetInfo::native("uusd"), &[])
+            .unwrap();
+        assert_eq!(
+            msgs,
+            vec![CosmosMsg::Wasm(WasmMsg::Execute {
+                contract_addr: "mock_router".to_string(),
+                msg: to_json_binary(&RouterExecuteMsg::ExecuteSwapOperations {
+                    operations: vec![SwapOperation::AstroSwap {
+                        offer_asset_info: astroport::asset::AssetInfo::NativeToken {
+                            denom: "uluna".to_string(),
+                        },
+                        ask_asset_info: astro_uusd,
+                    }],
+                    minimum_receive: None,
+                    to: None,
+                    max_spread: None,
+                })
+                .unwrap(),
+                funds: vec![Coin::new(100u128, "uluna")],
+            })]
+        );
+
+        let offer =
+            AssetList::try_from_assets(vec![Asset::cw721(Addr::unchecked("mock_nft"), "1")])
+                .unwrap();
+        assert_eq!(
+            router.swap_msgs(&offer, &AssetInfo::native("uusd"), &[]),
+            Err(StdError::generic_err("cw721 tokens can not be swapped"))
+        );
+    }
+
+    #[test]
+    fn simulating_through_router() {
+        let querier = mock_querier();
+        let querier: QuerierWrapper = QuerierWrapper::new(&querier);
+        let router = AstroportRouter::new(Addr::unchecked("mock_router"), None);
+
+        let offer = AssetList::try_from_assets(vec![
+            Asset::native("uluna", 100u128),
+            Asset::new(mock_token(), 88888u128),
+            Asset::native("uusd", 12345u128),
+        ])
+        .unwrap();
+        assert_eq!(
+            router
+                .simulate_swap(&querier, &offer, &AssetInfo::native("uusd"))
+                .unwrap(),
+            Uint128::new(50 + 44444)
+        );
+    }
+}
@@ -0,0 +1,8 @@
+//! Aliases over the API differences between the supported CosmWasm versions
+
+/// A `cw_storage_plus::Map`, which no longer takes a lifetime in cw-storage-plus
+/// 2.x
+#[cfg(feature = "cosmwasm-1")]
+pub(crate) type Map<'a, K, V> = cw_storage_plus::Map<'a, K, V>;
+#[cfg(not(feature = "cosmwasm-1"))]
+pub(crate) type Map<'a, K, V> = cw_storage_plus::Map<K, V>;
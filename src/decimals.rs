@@ -0,0 +1,196 @@
+use std::collections::HashMap;
+
+use cosmwasm_std::{StdError, StdResult};
+
+use super::asset_info::AssetInfo;
+use super::querier::AssetQuerier;
+
+/// A source of the number of decimals of assets, i.e. the number of base units
+/// in one whole unit of the asset expressed as a power of ten, and of the
+/// symbol of that whole unit
+pub trait DecimalsProvider {
+    /// Return the number of decimals of the asset
+    fn decimals(&self, querier: &dyn AssetQuerier, info: &AssetInfo) -> StdResult<u8>;
+
+    /// Return the symbol of one whole unit of the asset, e.g. `OSMO` for
+    /// `uosmo`
+    fn symbol(&self, querier: &dyn AssetQuerier, info: &AssetInfo) -> StdResult<String>;
+}
+
+/// A decimals provider that reads the decimals and symbols of CW20 tokens from
+/// their `TokenInfo`, and those of native coins from static maps, as native
+/// coins do not have on-chain metadata available to contracts
+#[derive(Clone, Debug, Default, PartialEq, Eq)]
+pub struct Cw20DecimalsProvider {
+    pub native_decimals: HashMap<String, u8>,
+    pub native_symbols: HashMap<String, String>,
+}
+
+impl Cw20DecimalsProvider {
+    /// Create a new decimals provider with the given native coin denoms,
+    /// symbols and decimals
+    pub fn new<I: IntoIterator<Item = (String, String, u8)>>(natives: I) -> Self {
+        let mut provider = Self::default();
+        for (denom, symbol, decimals) in natives {
+            provider.native_decimals.insert(denom.clone(), decimals);
+            provider.native_symbols.insert(denom, symbol);
+        }
+        provider
+    }
+}
+
+impl DecimalsProvider for Cw20DecimalsProvider {
+    fn decimals(&self, querier: &dyn AssetQuerier, info: &AssetInfo) -> StdResult<u8> {
+        match info {
+            AssetInfo::Cw20(_) => querier
+                .query_asset_metadata(info)?
+                .decimals
+                .ok_or_else(|| StdError::generic_err(format!("decimals not found: {}", info))),
+            AssetInfo::Cw721(..) => Ok(0),
+            AssetInfo::Native(denom) => self
+                .native_decimals
+                .get(denom)
+                .copied()
+                .ok_or_else(|| StdError::generic_err(format!("decimals not found: {}", denom))),
+        }
+    }
+
+    fn symbol(&self, querier: &dyn AssetQuerier, info: &AssetInfo) -> StdResult<String> {
+        match info {
+            AssetInfo::Cw20(_) | AssetInfo::Cw721(..) => {
+                Ok(querier.query_asset_metadata(info)?.symbol)
+            }
+            AssetInfo::Native(denom) => self
+                .native_symbols
+                .get(denom)
+                .cloned()
+                .ok_or_else(|| StdError::generic_err(format!("symbol not found: {}", denom))),
+        }
+    }
+}
+
+/// A decimals provider that reads the decimals and symbols of all assets from
+/// static maps
+#[derive(Clone, Debug, Default, PartialEq, Eq)]
+pub struct StaticDecimalsProvider {
+    pub decimals: HashMap<AssetInfo, u8>,
+    pub symbols: HashMap<AssetInfo, String>,
+}
+
+impl StaticDecimalsProvider {
+    /// Create a new decimals provider with the given assets, symbols and
+    /// decimals
+    pub fn new<I: IntoIterator<Item = (AssetInfo, String, u8)>>(assets: I) -> Self {
+        let mut provider = Self::default();
+        for (info, symbol, decimals) in assets {
+            provider.decimals.insert(info.clone(), decimals);
+            provider.symbols.insert(info, symbol);
+        }
+        provider
+    }
+}
+
+impl DecimalsProvider for StaticDecimalsProvider {
+    fn decimals(&self, _querier: &dyn AssetQuerier, info: &AssetInfo) -> StdResult<u8> {
+        self.decimals
+            .get(info)
+            .copied()
+            .ok_or_else(|| StdError::generic_err(format!("decimals not found: {}", info)))
+    }
+
+    fn symbol(&self, _querier: &dyn AssetQuerier, info: &AssetInfo) -> StdResult<String> {
+        self.symbols
+            .get(info)
+            .cloned()
+            .ok_or_else(|| StdError::generic_err(format!("symbol not found: {}", info)))
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use cosmwasm_std::testing::MockQuerier;
+    use cosmwasm_std::{
+        from_json, to_json_binary, Addr, ContractResult, QuerierWrapper, SystemResult, Uint128,
+        WasmQuery,
+    };
+    use cw20::{Cw20QueryMsg, TokenInfoResponse};
+
+    fn mock_token() -> AssetInfo {
+        AssetInfo::cw20(Addr::unchecked("mock_token"))
+    }
+
+    fn mock_querier() -> MockQuerier {
+        let mut querier = MockQuerier::new(&[]);
+        querier.update_wasm(|query| match query {
+            WasmQuery::Smart { contract_addr, msg } if contract_addr == "mock_token" => {
+                match from_json(msg).unwrap() {
+                    Cw20QueryMsg::TokenInfo {} => SystemResult::Ok(ContractResult::Ok(
+                        to_json_binary(&TokenInfoResponse {
+                            name: "Mock Token".to_string(),
+                            symbol: "MOCK".to_string(),
+                            decimals: 8,
+                            total_supply: Uint128::new(1_000_000),
+                        })
+                        .unwrap(),
+                    )),
+                    query => panic!("[mock]: unsupported cw20 query {:?}", query),
+                }
+            }
+            _ => panic!("[mock]: unsupported wasm query {:?}", query),
+        });
+        querier
+    }
+
+    #[test]
+    fn cw20_decimals() {
+        let querier = mock_querier();
+        let querier: QuerierWrapper = QuerierWrapper::new(&querier);
+
+        let provider =
+            Cw20DecimalsProvider::new(vec![("uosmo".to_string(), "OSMO".to_string(), 6)]);
+        assert_eq!(provider.decimals(&querier, &mock_token()).unwrap(), 8);
+        assert_eq!(provider.symbol(&querier, &mock_token()).unwrap(), "MOCK");
+        assert_eq!(
+            provider
+                .decimals(&querier, &AssetInfo::native("uosmo"))
+                .unwrap(),
+            6
+        );
+        assert_eq!(
+            provider
+                .symbol(&querier, &AssetInfo::native("uosmo"))
+                .unwrap(),
+            "OSMO"
+        );
+        assert_eq!(
+            provider.decimals(&querier, &AssetInfo::native("uatom")),
+            Err(StdError::generic_err("decimals not found: uatom"))
+        );
+        assert_eq!(
+            provider.symbol(&querier, &AssetInfo::native("uatom")),
+            Err(StdError::generic_err("symbol not found: uatom"))
+        );
+    }
+
+    #[test]
+    fn static_decimals() {
+        let querier = MockQuerier::default();
+        let querier: QuerierWrapper = QuerierWrapper::new(&querier);
+
+        let provider = StaticDecimalsProvider::new(vec![
+            (AssetInfo::native("uosmo"), "OSMO".to_string(), 6),
+            (mock_token(), "MOCK".to_string(), 18),
+        ]);
+        assert_eq!(provider.decimals(&querier, &mock_token()).unwrap(), 18);
+        assert_eq!(provider.symbol(&querier, &mock_token()).unwrap(), "MOCK");
+        assert_eq!(
+            provider.decimals(&querier, &AssetInfo::native("uatom")),
+            Err(StdError::generic_err("decimals not found: uatom"))
+        );
+        assert_eq!(
+            provider.symbol(&querier, &AssetInfo::native("uatom")),
+            Err(StdError::generic_err("symbol not found: uatom"))
+        );
+    }
+}
@@ -0,0 +1,35 @@
+use cosmwasm_std::StdError;
+use thiserror::Error;
+
+#[derive(Error, Debug, PartialEq)]
+pub enum AssetError {
+    #[error(transparent)]
+    Std(#[from] StdError),
+
+    #[error("cw1155 assets are not supported: {contract_addr}:{token_id}")]
+    UnsupportedCw1155 {
+        contract_addr: String,
+        token_id: String,
+    },
+
+    #[error("no contract found at address: {address}")]
+    NotAContract { address: String },
+
+    #[error("contract is not a cw20 token {address}: {error}")]
+    NotACw20 { address: String, error: StdError },
+
+    #[error("invalid bech32 address {address}: {reason}")]
+    InvalidBech32Address { address: String, reason: String },
+
+    #[error("invalid bech32 prefix: {prefix}")]
+    InvalidBech32Prefix { prefix: String },
+}
+
+impl From<AssetError> for StdError {
+    fn from(err: AssetError) -> Self {
+        match err {
+            AssetError::Std(err) => err,
+            err => StdError::generic_err(err.to_string()),
+        }
+    }
+}
@@ -0,0 +1,112 @@
+use std::fmt;
+
+use cosmwasm_std::{Attribute, Response, StdError, StdResult};
+
+use super::asset_list::AssetList;
+
+/// The prefix of the attributes of the assets embedded in asset events
+pub const ASSET_EVENT_PREFIX: &str = "asset";
+
+/// The kind of asset movement an event describes, used as the event type
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+pub enum AssetEventKind {
+    /// Assets moved from one address to another
+    Transfer,
+    /// Assets received by the contract, e.g. into a vault or pool
+    Deposit,
+    /// Assets sent out of the contract, e.g. from a vault or pool
+    Withdraw,
+}
+
+impl AssetEventKind {
+    /// Return the event type of the kind, e.g. `deposit`
+    pub fn as_str(&self) -> &'static str {
+        match self {
+            AssetEventKind::Transfer => "transfer",
+            AssetEventKind::Deposit => "deposit",
+            AssetEventKind::Withdraw => "withdraw",
+        }
+    }
+}
+
+impl fmt::Display for AssetEventKind {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        write!(f, "{}", self.as_str())
+    }
+}
+
+pub(crate) fn attribute_key(prefix: &str, name: &str) -> String {
+    if prefix.is_empty() {
+        name.to_string()
+    } else {
+        format!("{}_{}", prefix, name)
+    }
+}
+
+fn find_attribute<'a>(attributes: &'a [Attribute], key: &str) -> Option<&'a str> {
+    attributes
+        .iter()
+        .find(|attr| attr.key == key)
+        .map(|attr| attr.value.as_str())
+}
+
+pub(crate) fn get_attribute<'a>(attributes: &'a [Attribute], key: &str) -> StdResult<&'a str> {
+    find_attribute(attributes, key)
+        .ok_or_else(|| StdError::generic_err(format!("attribute not found: {}", key)))
+}
+
+/// Read the asset lists of all events of the given kind in a response, in the
+/// order the events were emitted
+pub fn parse_asset_events<T>(
+    response: &Response<T>,
+    kind: AssetEventKind,
+) -> StdResult<Vec<AssetList>> {
+    response
+        .events
+        .iter()
+        .filter(|event| event.ty == kind.as_str())
+        .map(|event| AssetList::from_attributes(ASSET_EVENT_PREFIX, &event.attributes))
+        .collect()
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::Asset;
+    use cosmwasm_std::Addr;
+
+    fn mock_list() -> AssetList {
+        AssetList::try_from_assets(vec![
+            Asset::native("uusd", 69420u128),
+            Asset::cw20(Addr::unchecked("mock_token"), 88888u128),
+        ])
+        .unwrap()
+    }
+
+    #[test]
+    fn events() {
+        let deposit = AssetList::try_from_assets(vec![Asset::native("uusd", 1u128)]).unwrap();
+        let response: Response = Response::new()
+            .add_event(
+                deposit
+                    .to_event(AssetEventKind::Deposit)
+                    .add_attribute("sender", "alice"),
+            )
+            .add_event(mock_list().to_event(AssetEventKind::Withdraw))
+            .add_event(AssetList::new().to_event(AssetEventKind::Withdraw));
+
+        assert_eq!(response.events[0].ty, "deposit");
+        assert_eq!(
+            parse_asset_events(&response, AssetEventKind::Deposit).unwrap(),
+            vec![deposit]
+        );
+        assert_eq!(
+            parse_asset_events(&response, AssetEventKind::Withdraw).unwrap(),
+            vec![mock_list(), AssetList::new()]
+        );
+        assert_eq!(
+            parse_asset_events(&response, AssetEventKind::Transfer).unwrap(),
+            vec![]
+        );
+    }
+}
@@ -1,10 +1,63 @@
+#[cfg(not(any(feature = "cosmwasm-1", feature = "cosmwasm-2")))]
+compile_error!("either the `cosmwasm-1` or the `cosmwasm-2` feature must be enabled");
+#[cfg(all(feature = "cosmwasm-1", feature = "cosmwasm-2"))]
+compile_error!(
+    "the `cosmwasm-1` and `cosmwasm-2` features are mutually exclusive; disable the default \
+     features to build against cosmwasm-std 2.x"
+);
+
+#[cfg(not(feature = "cosmwasm-1"))]
+extern crate cosmwasm_std_2 as cosmwasm_std;
+#[cfg(not(feature = "cosmwasm-1"))]
+extern crate cw20_2 as cw20;
+#[cfg(not(feature = "cosmwasm-1"))]
+extern crate cw_storage_plus_2 as cw_storage_plus;
+
+mod any_format;
 mod asset;
 mod asset_info;
+mod asset_info_pair;
+mod asset_ledger;
 mod asset_list;
+mod asset_registry;
+mod compat;
+mod cw721;
+mod decimals;
+mod error;
+mod events;
+mod lp_asset;
+mod price_source;
+mod querier;
+mod swap_router;
 
+pub use any_format::*;
 pub use asset::*;
 pub use asset_info::*;
+pub use asset_info_pair::*;
+pub use asset_ledger::*;
 pub use asset_list::*;
+pub use asset_registry::*;
+pub use decimals::*;
+pub use error::*;
+pub use events::*;
+pub use lp_asset::*;
+pub use price_source::*;
+pub use querier::*;
+pub use swap_router::*;
+
+#[cfg(feature = "astroport")]
+pub mod astroport;
+
+#[cfg(feature = "osmosis")]
+pub mod osmosis;
+
+pub mod pool_math;
+
+#[cfg(feature = "terra-classic")]
+pub mod terra;
+
+#[cfg(feature = "vault-standard")]
+pub mod vault;
 
-#[cfg(all(test, feature = "terra"))]
+#[cfg(test)]
 mod testing;
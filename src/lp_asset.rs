@@ -0,0 +1,238 @@
+use cosmwasm_std::{Addr, StdError, StdResult, Uint128};
+
+use schemars::JsonSchema;
+use serde::{Deserialize, Serialize};
+
+use crate::pool_math::Pool;
+use crate::AssetQuerier;
+
+use super::asset::Asset;
+use super::asset_info::AssetInfo;
+use super::asset_list::AssetList;
+
+/// A liquidity pool share token, and the basket of assets it is a share of
+///
+/// The LP token itself is an ordinary CW20 token or native (e.g. token factory)
+/// denom, but each unit of it is worth `pool / total_supply`.
+///
+/// **Usage:**
+/// The following code computes the assets withdrawn for redeeming 100 LP tokens.
+///
+/// ```rust
+/// let lp = LpAsset::query(&deps.querier, lp_info, &pool_addr, pool_infos)?;
+/// let assets = lp.underlying(Uint128::new(100))?;
+/// ```
+#[derive(Serialize, Deserialize, Clone, Debug, PartialEq, Eq, JsonSchema)]
+pub struct LpAsset {
+    /// The LP token
+    pub lp: AssetInfo,
+    /// The reserves of the pool
+    pub pool: AssetList,
+    /// The total supply of the LP token
+    pub total_supply: Uint128,
+}
+
+impl LpAsset {
+    /// Create a new LP asset with the given pool reserves and LP token supply
+    pub fn new(lp: AssetInfo, pool: AssetList, total_supply: Uint128) -> Self {
+        Self {
+            lp,
+            pool,
+            total_supply,
+        }
+    }
+
+    /// Create a new LP asset, querying the balances of the given assets held
+    /// by the pool and the total supply of the LP token
+    pub fn query(
+        querier: &dyn AssetQuerier,
+        lp: AssetInfo,
+        pool_addr: &Addr,
+        pool_infos: Vec<AssetInfo>,
+    ) -> StdResult<Self> {
+        let pool = AssetList::query_asset_info_balances(pool_infos, querier, pool_addr)?;
+        Self::query_supply(querier, lp, pool)
+    }
+
+    /// Create a new LP asset with the given pool reserves, querying the total
+    /// supply of the LP token
+    ///
+    /// Use this if the pool reports its reserves itself, rather than holding
+    /// them as balances, e.g. if it keeps them in a separate contract.
+    pub fn query_supply(
+        querier: &dyn AssetQuerier,
+        lp: AssetInfo,
+        pool: AssetList,
+    ) -> StdResult<Self> {
+        let total_supply = querier.query_asset_supply(&lp)?;
+        Ok(Self::new(lp, pool, total_supply))
+    }
+
+    /// Return the LP token of the given amount
+    pub fn to_asset<B: Into<Uint128>>(&self, amount: B) -> Asset {
+        Asset::new(self.lp.clone(), amount)
+    }
+
+    /// Return the share of the pool's assets that the given amount of LP tokens
+    /// is worth, rounded down
+    pub fn underlying<B: Into<Uint128>>(&self, amount: B) -> StdResult<AssetList> {
+        let amount = amount.into();
+        if amount > self.total_supply {
+            return Err(StdError::generic_err(format!(
+                "{} exceeds the total supply of {}",
+                self.to_asset(amount),
+                self.total_supply
+            )));
+        }
+        if amount.is_zero() {
+            return Ok(AssetList::new());
+        }
+        Pool::xyk(self.pool.clone()).withdraw_liquidity(amount, self.total_supply)
+    }
+
+    /// Return the amount of LP tokens that the given assets are worth, rounded
+    /// down
+    ///
+    /// Assets deposited in excess of the pool's ratio are not counted, so this
+    /// is the smallest share of the pool represented by any of the assets.
+    /// Returns an error if any of the assets is not in the pool, or if the pool
+    /// has no LP tokens yet, since the first deposit sets the share price.
+    pub fn shares_for(&self, assets: &AssetList) -> StdResult<Uint128> {
+        if self.total_supply.is_zero() {
+            return Err(StdError::generic_err(format!(
+                "{} has no supply to value assets against",
+                self.lp
+            )));
+        }
+        Pool::xyk(self.pool.clone()).provide_liquidity_shares(assets, self.total_supply)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use cosmwasm_std::testing::MockQuerier;
+    use cosmwasm_std::{
+        from_json, to_json_binary, Coin, ContractResult, QuerierWrapper, SystemResult, WasmQuery,
+    };
+    use cw20::{BalanceResponse, Cw20QueryMsg, TokenInfoResponse};
+
+    fn mock_token() -> AssetInfo {
+        AssetInfo::cw20(Addr::unchecked("mock_token"))
+    }
+
+    fn mock_lp() -> LpAsset {
+        LpAsset::new(
+            AssetInfo::native("factory/pool/lp"),
+            AssetList::try_from_assets(vec![
+                Asset::native("uosmo", 1_000_000u128),
+                Asset::new(mock_token(), 2_000_000u128),
+            ])
+            .unwrap(),
+            Uint128::new(3000),
+        )
+    }
+
+    #[test]
+    fn querying() {
+        let mut querier: MockQuerier =
+            MockQuerier::new(&[("pool", &[Coin::new(1_000_000u128, "uosmo")])]);
+        querier.update_wasm(|query| match query {
+            WasmQuery::Smart { contract_addr, msg } if contract_addr == "mock_token" => {
+                let res = match from_json(msg).unwrap() {
+                    Cw20QueryMsg::Balance { address } => to_json_binary(&BalanceResponse {
+                        balance: Uint128::new(if address == "pool" { 2_000_000 } else { 0 }),
+                    }),
+                    query => panic!("[mock]: unsupported cw20 query {:?}", query),
+                };
+                SystemResult::Ok(ContractResult::Ok(res.unwrap()))
+            }
+            WasmQuery::Smart { contract_addr, msg } if contract_addr == "lp_token" => {
+                let res = match from_json(msg).unwrap() {
+                    Cw20QueryMsg::TokenInfo {} => to_json_binary(&TokenInfoResponse {
+                        name: "Pool LP".to_string(),
+                        symbol: "LP".to_string(),
+                        decimals: 6,
+                        total_supply: Uint128::new(3000),
+                    }),
+                    query => panic!("[mock]: unsupported cw20 query {:?}", query),
+                };
+                SystemResult::Ok(ContractResult::Ok(res.unwrap()))
+            }
+            _ => panic!("[mock]: unsupported wasm query {:?}", query),
+        });
+        let querier: QuerierWrapper = QuerierWrapper::new(&querier);
+
+        let lp_token = AssetInfo::cw20(Addr::unchecked("lp_token"));
+        let lp = LpAsset::query(
+            &querier,
+            lp_token.clone(),
+            &Addr::unchecked("pool"),
+            vec![AssetInfo::native("uosmo"), mock_token()],
+        )
+        .unwrap();
+        assert_eq!(
+            lp,
+            LpAsset {
+                lp: lp_token,
+                ..mock_lp()
+            }
+        );
+    }
+
+    #[test]
+    fn unwrapping() {
+        let lp = mock_lp();
+        assert_eq!(
+            lp.underlying(1000u128).unwrap(),
+            AssetList::try_from_assets(vec![
+                Asset::native("uosmo", 333_333u128),
+                Asset::new(mock_token(), 666_666u128),
+            ])
+            .unwrap()
+        );
+        assert_eq!(lp.underlying(3000u128).unwrap(), lp.pool);
+        assert_eq!(lp.underlying(0u128).unwrap(), AssetList::new());
+        assert_eq!(
+            lp.underlying(3001u128),
+            Err(StdError::generic_err(
+                "factory/pool/lp:3001 exceeds the total supply of 3000"
+            ))
+        );
+    }
+
+    #[test]
+    fn pricing_shares() {
+        let lp = mock_lp();
+        let assets = AssetList::try_from_assets(vec![
+            Asset::native("uosmo", 1000u128),
+            Asset::new(mock_token(), 2000u128),
+        ])
+        .unwrap();
+        assert_eq!(lp.shares_for(&assets).unwrap(), Uint128::new(3));
+
+        // the excess of uosmo is not counted, and a missing asset is worth nothing
+        let assets = AssetList::try_from_assets(vec![
+            Asset::native("uosmo", 5000u128),
+            Asset::new(mock_token(), 2000u128),
+        ])
+        .unwrap();
+        assert_eq!(lp.shares_for(&assets).unwrap(), Uint128::new(3));
+        let assets = AssetList::try_from_assets(vec![Asset::native("uosmo", 5000u128)]).unwrap();
+        assert_eq!(lp.shares_for(&assets).unwrap(), Uint128::zero());
+
+        let assets = AssetList::try_from_assets(vec![Asset::native("uatom", 1000u128)]).unwrap();
+        assert_eq!(
+            lp.shares_for(&assets),
+            Err(StdError::generic_err("asset not in pool: uatom"))
+        );
+
+        let lp = LpAsset::new(lp.lp, lp.pool, Uint128::zero());
+        assert_eq!(
+            lp.shares_for(&AssetList::new()),
+            Err(StdError::generic_err(
+                "factory/pool/lp has no supply to value assets against"
+            ))
+        );
+    }
+}
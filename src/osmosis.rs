@@ -0,0 +1,262 @@
+use std::convert::TryInto;
+
+use cosmwasm_std::{CosmosMsg, StdError, StdResult, Uint128};
+use osmosis_std::types::cosmos::base::v1beta1::Coin as ProtoCoin;
+use osmosis_std::types::osmosis::gamm::v1beta1::{MsgExitPool, MsgJoinPool, MsgSwapExactAmountIn};
+use osmosis_std::types::osmosis::poolmanager::v1beta1::SwapAmountInRoute;
+
+use crate::{Asset, AssetInfo, AssetList};
+
+/// Generate a message that swaps the offer asset for at least `min_out` of the
+/// ask asset in the given Osmosis pool
+///
+/// NOTE: Only works for native coins
+///
+/// **Usage:**
+/// The following code generates a message that swaps 12345 uosmo for at least
+/// 100 uatom in pool 1.
+///
+/// ```rust
+/// let offer = Asset::native("uosmo", 12345u128);
+/// let msg = swap_exact_amount_in_msg(env.contract.address, 1, &offer, &AssetInfo::native("uatom"), 100u128.into())?;
+/// ```
+pub fn swap_exact_amount_in_msg<A: Into<String>>(
+    sender: A,
+    pool_id: u64,
+    offer: &Asset,
+    ask: &AssetInfo,
+    min_out: Uint128,
+) -> StdResult<CosmosMsg> {
+    let token_out_denom = match ask {
+        AssetInfo::Native(denom) => denom.clone(),
+        AssetInfo::Cw20(_) => {
+            return Err(StdError::generic_err(
+                "Osmosis pools do not support CW20 tokens",
+            ))
+        }
+        AssetInfo::Cw721(..) => {
+            return Err(StdError::generic_err(
+                "Osmosis pools do not support CW721 tokens",
+            ))
+        }
+    };
+    Ok(MsgSwapExactAmountIn {
+        sender: sender.into(),
+        routes: vec![SwapAmountInRoute {
+            pool_id,
+            token_out_denom,
+        }],
+        token_in: Some(offer.try_into()?),
+        token_out_min_amount: min_out.to_string(),
+    }
+    .into())
+}
+
+/// Generate a message that joins the given Osmosis pool for `share_out_amount`
+/// pool shares, spending at most `token_in_maxs`
+///
+/// NOTE: Only works for lists of native coins. The coins are sorted by denom,
+/// as the SDK requires.
+pub fn join_pool_msg<A: Into<String>>(
+    sender: A,
+    pool_id: u64,
+    share_out_amount: Uint128,
+    token_in_maxs: &AssetList,
+) -> StdResult<CosmosMsg> {
+    Ok(MsgJoinPool {
+        sender: sender.into(),
+        pool_id,
+        share_out_amount: share_out_amount.to_string(),
+        token_in_maxs: sorted_proto_coins(token_in_maxs)?,
+    }
+    .into())
+}
+
+/// Generate a message that exits the given Osmosis pool by burning
+/// `share_in_amount` pool shares, receiving at least `token_out_mins`
+///
+/// NOTE: Only works for lists of native coins. The coins are sorted by denom,
+/// as the SDK requires.
+pub fn exit_pool_msg<A: Into<String>>(
+    sender: A,
+    pool_id: u64,
+    share_in_amount: Uint128,
+    token_out_mins: &AssetList,
+) -> StdResult<CosmosMsg> {
+    Ok(MsgExitPool {
+        sender: sender.into(),
+        pool_id,
+        share_in_amount: share_in_amount.to_string(),
+        token_out_mins: sorted_proto_coins(token_out_mins)?,
+    }
+    .into())
+}
+
+// the SDK requires `sdk.Coins` to be sorted by denom, while an `AssetList` is
+// in insertion order
+fn sorted_proto_coins(list: &AssetList) -> StdResult<Vec<ProtoCoin>> {
+    let mut coins: Vec<ProtoCoin> = list.try_into()?;
+    coins.sort_by(|a, b| a.denom.cmp(&b.denom));
+    Ok(coins)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use cosmwasm_std::Addr;
+
+    fn proto_coin(amount: u128, denom: &str) -> ProtoCoin {
+        ProtoCoin {
+            denom: denom.to_string(),
+            amount: amount.to_string(),
+        }
+    }
+
+    fn mock_list() -> AssetList {
+        AssetList::try_from_assets(vec![
+            Asset::native("uatom", 69420u128),
+            Asset::native("uosmo", 88888u128),
+        ])
+        .unwrap()
+    }
+
+    #[test]
+    fn converting_coins() {
+        let coin: ProtoCoin = Asset::native("uosmo", 12345u128).try_into().unwrap();
+        assert_eq!(coin, proto_coin(12345, "uosmo"));
+        assert_eq!(
+            Asset::try_from(coin).unwrap(),
+            Asset::native("uosmo", 12345u128)
+        );
+
+        let res: StdResult<ProtoCoin> =
+            Asset::cw20(Addr::unchecked("mock_token"), 1u128).try_into();
+        assert!(res.is_err());
+        assert!(Asset::try_from(proto_coin(1, "uosmo")).is_ok());
+        assert!(Asset::try_from(ProtoCoin {
+            denom: "uosmo".to_string(),
+            amount: "one".to_string(),
+        })
+        .is_err());
+
+        let coins: Vec<ProtoCoin> = mock_list().try_into().unwrap();
+        assert_eq!(
+            coins,
+            vec![proto_coin(69420, "uatom"), proto_coin(88888, "uosmo")]
+        );
+        assert_eq!(AssetList::try_from_proto_coins(coins).unwrap(), mock_list());
+    }
+
+    #[test]
+    fn creating_swap_msg() {
+        let offer = Asset::native("uosmo", 12345u128);
+        let msg = swap_exact_amount_in_msg(
+            "alice",
+            1,
+            &offer,
+            &AssetInfo::native("uatom"),
+            Uint128::new(100),
+        )
+        .unwrap();
+        assert_eq!(
+            msg,
+            MsgSwapExactAmountIn {
+                sender: "alice".to_string(),
+                routes: vec![SwapAmountInRoute {
+                    pool_id: 1,
+                    token_out_denom: "uatom".to_string(),
+                }],
+                token_in: Some(proto_coin(12345, "uosmo")),
+                token_out_min_amount: "100".to_string(),
+            }
+            .into()
+        );
+
+        let err = swap_exact_amount_in_msg(
+            "alice",
+            1,
+            &offer,
+            &AssetInfo::cw20(Addr::unchecked("mock_token")),
+            Uint128::new(100),
+        );
+        assert_eq!(
+            err,
+            Err(StdError::generic_err(
+                "Osmosis pools do not support CW20 tokens"
+            ))
+        );
+    }
+
+    #[test]
+    fn creating_pool_msgs() {
+        let msg = join_pool_msg("alice", 1, Uint128::new(1000), &mock_list()).unwrap();
+        assert_eq!(
+            msg,
+            MsgJoinPool {
+                sender: "alice".to_string(),
+                pool_id: 1,
+                share_out_amount: "1000".to_string(),
+                token_in_maxs: vec![proto_coin(69420, "uatom"), proto_coin(88888, "uosmo")],
+            }
+            .into()
+        );
+
+        let msg = exit_pool_msg("alice", 1, Uint128::new(1000), &mock_list()).unwrap();
+        assert_eq!(
+            msg,
+            MsgExitPool {
+                sender: "alice".to_string(),
+                pool_id: 1,
+                share_in_amount: "1000".to_string(),
+                token_out_mins: vec![proto_coin(69420, "uatom"), proto_coin(88888, "uosmo")],
+            }
+            .into()
+        );
+
+        // coins are sorted by denom regardless of the list's order
+        let list = AssetList::try_from_assets(vec![
+            Asset::native("uosmo", 88888u128),
+            Asset::native(
+                "ibc/27394FB092D2ECCD56123C74F36E4C1F926001CEADA9CA97EA622B25F41E5EB2",
+                1u128,
+            ),
+            Asset::native("uatom", 69420u128),
+        ])
+        .unwrap();
+        let sorted = vec![
+            proto_coin(
+                1,
+                "ibc/27394FB092D2ECCD56123C74F36E4C1F926001CEADA9CA97EA622B25F41E5EB2",
+            ),
+            proto_coin(69420, "uatom"),
+            proto_coin(88888, "uosmo"),
+        ];
+        let msg = join_pool_msg("alice", 1, Uint128::new(1000), &list).unwrap();
+        assert_eq!(
+            msg,
+            MsgJoinPool {
+                sender: "alice".to_string(),
+                pool_id: 1,
+                share_out_amount: "1000".to_string(),
+                token_in_maxs: sorted.clone(),
+            }
+            .into()
+        );
+        let msg = exit_pool_msg("alice", 1, Uint128::new(1000), &list).unwrap();
+        assert_eq!(
+            msg,
+            MsgExitPool {
+                sender: "alice".to_string(),
+                pool_id: 1,
+                share_in_amount: "1000".to_string(),
+                token_out_mins: sorted,
+            }
+            .into()
+        );
+
+        let list =
+            AssetList::try_from_assets(vec![Asset::cw20(Addr::unchecked("mock_token"), 1u128)])
+                .unwrap();
+        assert!(join_pool_msg("alice", 1, Uint128::new(1000), &list).is_err());
+    }
+}
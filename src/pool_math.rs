@@ -0,0 +1,650 @@
+//! Swap and liquidity math of constant product (xyk) and stableswap pools
+//!
+//! All computations use `Uint256` intermediates, and round in favour of the
+//! pool: swaps return less and cost more, deposits mint fewer shares, and
+//! withdrawals return fewer assets, by at most one base unit.
+
+use cosmwasm_std::{Decimal, Isqrt, StdError, StdResult, Uint128, Uint256};
+
+use crate::{Asset, AssetInfo, AssetList};
+
+/// The maximum number of Newton iterations of the stableswap invariant
+const MAX_ITERATIONS: usize = 256;
+
+/// The invariant of a pool
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+pub enum Curve {
+    /// The constant product invariant `x * y = k`
+    Xyk,
+    /// The stableswap invariant with the given amplification coefficient
+    ///
+    /// NOTE: The invariant assumes that one base unit of every asset is worth
+    /// about the same, so the reserves of assets with different decimals must
+    /// be normalized first.
+    Stable { amp: u64 },
+}
+
+/// The result of simulating a swap
+#[derive(Clone, Debug, PartialEq, Eq)]
+pub struct SwapSimulation {
+    /// The asset offered to the pool
+    pub offer: Asset,
+    /// The asset returned by the pool, after the fee
+    pub return_asset: Asset,
+    /// The fee retained by the pool, in the ask asset
+    pub fee: Asset,
+}
+
+/// A pool of the given reserves and invariant
+#[derive(Clone, Debug, PartialEq, Eq)]
+pub struct Pool {
+    pub reserves: AssetList,
+    pub curve: Curve,
+}
+
+impl Pool {
+    /// Create a new pool with the given reserves and invariant
+    pub fn new(reserves: AssetList, curve: Curve) -> Self {
+        Self { reserves, curve }
+    }
+
+    /// Create a new constant product pool with the given reserves
+    pub fn xyk(reserves: AssetList) -> Self {
+        Self::new(reserves, Curve::Xyk)
+    }
+
+    /// Create a new stableswap pool with the given reserves and amplification
+    /// coefficient
+    pub fn stable(reserves: AssetList, amp: u64) -> Self {
+        Self::new(reserves, Curve::Stable { amp })
+    }
+
+    /// Return the amount of the ask asset the pool returns for the offer asset,
+    /// with the fee rate deducted from the returned amount
+    pub fn simulate_swap(
+        &self,
+        offer: &Asset,
+        ask: &AssetInfo,
+        fee: Decimal,
+    ) -> StdResult<SwapSimulation> {
+        let (i, j) = self.indices(&offer.info, ask)?;
+        let xp = self.reserves_u256();
+        let dx = Uint256::from(offer.amount);
+
+        let gross = match self.curve {
+            Curve::Xyk => xp[j]
+                .checked_multiply_ratio(dx, xp[i].checked_add(dx)?)
+                .map_err(|err| StdError::generic_err(err.to_string()))?,
+            Curve::Stable { amp } => {
+                let d = compute_d(&xp, amp)?;
+                let y = compute_y(i, j, xp[i].checked_add(dx)?, &xp, amp, d)?;
+                // deduct one unit to cover the error of the approximation
+                xp[j].saturating_sub(y).saturating_sub(Uint256::one())
+            }
+        };
+        let fee_amount = mul_ceil(gross, fee)?;
+        Ok(SwapSimulation {
+            offer: offer.clone(),
+            return_asset: Asset::new(ask.clone(), to_uint128(gross - fee_amount)?),
+            fee: Asset::new(ask.clone(), to_uint128(fee_amount)?),
+        })
+    }
+
+    /// Return the amount of the offer asset required for the pool to return
+    /// at least the ask asset, after the fee
+    pub fn reverse_simulate(
+        &self,
+        ask: &Asset,
+        offer: &AssetInfo,
+        fee: Decimal,
+    ) -> StdResult<SwapSimulation> {
+        if fee >= Decimal::one() {
+            return Err(StdError::generic_err("fee must be less than 1"));
+        }
+        let (i, j) = self.indices(offer, &ask.info)?;
+        let xp = self.reserves_u256();
+
+        let one = Uint256::from(Decimal::one().atomics());
+        let gross = div_ceil(
+            Uint256::from(ask.amount).checked_mul(one)?,
+            one - Uint256::from(fee.atomics()),
+        )?;
+        if gross >= xp[j] {
+            return Err(StdError::generic_err(format!(
+                "insufficient liquidity: {}",
+                ask
+            )));
+        }
+
+        let dx = match self.curve {
+            Curve::Xyk => div_ceil(xp[i].checked_mul(gross)?, xp[j] - gross)?,
+            Curve::Stable { amp } => {
+                let d = compute_d(&xp, amp)?;
+                let y = xp[j]
+                    .checked_sub(gross)?
+                    .checked_sub(Uint256::one())
+                    .map_err(|_| {
+                        StdError::generic_err(format!("insufficient liquidity: {}", ask))
+                    })?;
+                let x = compute_y(j, i, y, &xp, amp, d)?;
+                x.saturating_sub(xp[i]).checked_add(Uint256::one())?
+            }
+        };
+        self.simulate_swap(&Asset::new(offer.clone(), to_uint128(dx)?), &ask.info, fee)
+    }
+
+    /// Return the number of shares minted for depositing the assets, given
+    /// the total number of shares before the deposit
+    ///
+    /// The first deposit, i.e. if `total_shares` is zero, must contain every
+    /// asset of the pool. It mints the geometric mean of the two deposited
+    /// amounts for xyk pools, and the invariant of the deposited amounts for
+    /// stableswap pools. For xyk pools, assets deposited in excess of the
+    /// pool's ratio do not mint shares.
+    pub fn provide_liquidity_shares(
+        &self,
+        deposit: &AssetList,
+        total_shares: Uint128,
+    ) -> StdResult<Uint128> {
+        for asset in deposit {
+            self.index(&asset.info)?;
+        }
+
+        if total_shares.is_zero() {
+            let amounts = self
+                .reserves
+                .into_iter()
+                .map(|reserve| {
+                    deposit
+                        .find(&reserve.info)
+                        .filter(|asset| !asset.amount.is_zero())
+                        .map(|asset| Uint256::from(asset.amount))
+                        .ok_or_else(|| {
+                            StdError::generic_err(format!(
+                                "the first deposit must contain every asset of the pool, missing {}",
+                                reserve.info
+                            ))
+                        })
+                })
+                .collect::<StdResult<Vec<_>>>()?;
+            return match self.curve {
+                Curve::Xyk => match amounts.as_slice() {
+                    [a, b] => to_uint128(a.checked_mul(*b)?.isqrt()),
+                    _ => Err(StdError::generic_err("an xyk pool must contain 2 assets")),
+                },
+                Curve::Stable { amp } => to_uint128(compute_d(&amounts, amp)?),
+            };
+        }
+
+        let total_shares = Uint256::from(total_shares);
+        let xp = self.reserves_u256();
+        let deposits: Vec<Uint256> = self
+            .reserves
+            .into_iter()
+            .map(|reserve| {
+                Uint256::from(
+                    deposit
+                        .find(&reserve.info)
+                        .map(|asset| asset.amount)
+                        .unwrap_or_default(),
+                )
+            })
+            .collect();
+
+        let shares = match self.curve {
+            Curve::Xyk => xp
+                .iter()
+                .zip(&deposits)
+                .map(|(reserve, deposit)| {
+                    total_shares
+                        .checked_multiply_ratio(*deposit, *reserve)
+                        .map_err(|err| StdError::generic_err(err.to_string()))
+                })
+                .collect::<StdResult<Vec<_>>>()?
+                .into_iter()
+                .min()
+                .unwrap_or_default(),
+            Curve::Stable { amp } => {
+                let d0 = compute_d(&xp, amp)?;
+                let new_xp = xp
+                    .iter()
+                    .zip(&deposits)
+                    .map(|(reserve, deposit)| reserve.checked_add(*deposit))
+                    .collect::<Result<Vec<_>, _>>()?;
+                let d1 = compute_d(&new_xp, amp)?;
+                // deduct one unit to cover the error of the approximations
+                total_shares
+                    .checked_multiply_ratio(d1.saturating_sub(d0), d0)
+                    .map_err(|err| StdError::generic_err(err.to_string()))?
+                    .saturating_sub(Uint256::one())
+            }
+        };
+        to_uint128(shares)
+    }
+
+    /// Return the assets withdrawn for burning the shares, given the total
+    /// number of shares before the withdrawal
+    pub fn withdraw_liquidity(
+        &self,
+        shares: Uint128,
+        total_shares: Uint128,
+    ) -> StdResult<AssetList> {
+        if shares > total_shares {
+            return Err(StdError::generic_err(format!(
+                "cannot withdraw {} of {} shares",
+                shares, total_shares
+            )));
+        }
+        self.reserves
+            .into_iter()
+            .map(|asset| {
+                let amount = asset
+                    .amount
+                    .checked_multiply_ratio(shares, total_shares)
+                    .map_err(|err| StdError::generic_err(err.to_string()))?;
+                Ok(Asset::new(asset.info.clone(), amount))
+            })
+            .collect::<StdResult<Vec<Asset>>>()
+            .and_then(AssetList::try_from_assets)
+    }
+
+    fn index(&self, info: &AssetInfo) -> StdResult<usize> {
+        self.reserves
+            .into_iter()
+            .position(|asset| asset.info == *info)
+            .ok_or_else(|| StdError::generic_err(format!("asset not in pool: {}", info)))
+    }
+
+    fn indices(&self, offer: &AssetInfo, ask: &AssetInfo) -> StdResult<(usize, usize)> {
+        if offer == ask {
+            return Err(StdError::generic_err(format!(
+                "cannot swap {} for itself",
+                offer
+            )));
+        }
+        Ok((self.index(offer)?, self.index(ask)?))
+    }
+
+    fn reserves_u256(&self) -> Vec<Uint256> {
+        self.reserves
+            .into_iter()
+            .map(|asset| Uint256::from(asset.amount))
+            .collect()
+    }
+}
+
+fn to_uint128(value: Uint256) -> StdResult<Uint128> {
+    Ok(Uint128::try_from(value)?)
+}
+
+fn div_ceil(numerator: Uint256, denominator: Uint256) -> StdResult<Uint256> {
+    let quotient = numerator.checked_div(denominator)?;
+    if quotient.checked_mul(denominator)? < numerator {
+        Ok(quotient + Uint256::one())
+    } else {
+        Ok(quotient)
+    }
+}
+
+fn mul_ceil(amount: Uint256, rate: Decimal) -> StdResult<Uint256> {
+    if rate >= Decimal::one() {
+        return Err(StdError::generic_err("fee must be less than 1"));
+    }
+    div_ceil(
+        amount.checked_mul(Uint256::from(rate.atomics()))?,
+        Uint256::from(Decimal::one().atomics()),
+    )
+}
+
+fn abs_diff(a: Uint256, b: Uint256) -> Uint256 {
+    if a > b {
+        a - b
+    } else {
+        b - a
+    }
+}
+
+/// Compute the stableswap invariant `D` of the reserves with Newton's method
+fn compute_d(xp: &[Uint256], amp: u64) -> StdResult<Uint256> {
+    let n = Uint256::from(xp.len() as u128);
+    let sum = xp
+        .iter()
+        .try_fold(Uint256::zero(), |sum, x| sum.checked_add(*x))?;
+    if sum.is_zero() {
+        return Ok(Uint256::zero());
+    }
+    let ann = Uint256::from(amp).checked_mul(n)?;
+
+    let mut d = sum;
+    for _ in 0..MAX_ITERATIONS {
+        let mut d_p = d;
+        for x in xp {
+            d_p = d_p.checked_mul(d)?.checked_div(x.checked_mul(n)?)?;
+        }
+        let d_prev = d;
+        let numerator = ann
+            .checked_mul(sum)?
+            .checked_add(d_p.checked_mul(n)?)?
+            .checked_mul(d)?;
+        let denominator = ann
+            .checked_sub(Uint256::one())?
+            .checked_mul(d)?
+            .checked_add(n.checked_add(Uint256::one())?.checked_mul(d_p)?)?;
+        d = numerator.checked_div(denominator)?;
+        if abs_diff(d, d_prev) <= Uint256::one() {
+            return Ok(d);
+        }
+    }
+    Err(StdError::generic_err(
+        "stableswap invariant did not converge",
+    ))
+}
+
+/// Compute the reserve of asset `j` that keeps the invariant `d` if the
+/// reserve of asset `i` is changed to `x`
+fn compute_y(
+    i: usize,
+    j: usize,
+    x: Uint256,
+    xp: &[Uint256],
+    amp: u64,
+    d: Uint256,
+) -> StdResult<Uint256> {
+    let n = Uint256::from(xp.len() as u128);
+    let ann = Uint256::from(amp).checked_mul(n)?;
+
+    let mut c = d;
+    let mut sum = Uint256::zero();
+    for (k, reserve) in xp.iter().enumerate() {
+        let x_k = match k {
+            k if k == i => x,
+            k if k == j => continue,
+            _ => *reserve,
+        };
+        sum = sum.checked_add(x_k)?;
+        c = c.checked_mul(d)?.checked_div(x_k.checked_mul(n)?)?;
+    }
+    c = c.checked_mul(d)?.checked_div(ann.checked_mul(n)?)?;
+    let b = sum.checked_add(d.checked_div(ann)?)?;
+
+    let mut y = d;
+    for _ in 0..MAX_ITERATIONS {
+        let y_prev = y;
+        y = y.checked_mul(y)?.checked_add(c)?.checked_div(
+            y.checked_mul(Uint256::from(2u8))?
+                .checked_add(b)?
+                .checked_sub(d)?,
+        )?;
+        if abs_diff(y, y_prev) <= Uint256::one() {
+            return Ok(y);
+        }
+    }
+    Err(StdError::generic_err(
+        "stableswap invariant did not converge",
+    ))
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use cosmwasm_std::Addr;
+
+    use test_case::test_case;
+
+    fn uosmo() -> AssetInfo {
+        AssetInfo::native("uosmo")
+    }
+
+    fn uatom() -> AssetInfo {
+        AssetInfo::native("uatom")
+    }
+
+    fn xyk_pool() -> Pool {
+        Pool::xyk(
+            AssetList::try_from_assets(vec![
+                Asset::new(uosmo(), 1_000_000u128),
+                Asset::new(uatom(), 2_000_000u128),
+            ])
+            .unwrap(),
+        )
+    }
+
+    fn stable_pool() -> Pool {
+        Pool::stable(
+            AssetList::try_from_assets(vec![
+                Asset::new(uosmo(), 1_000_000_000u128),
+                Asset::new(uatom(), 1_000_000_000u128),
+            ])
+            .unwrap(),
+            100,
+        )
+    }
+
+    #[test]
+    fn swapping_xyk() {
+        let sim = xyk_pool()
+            .simulate_swap(
+                &Asset::new(uosmo(), 10_000u128),
+                &uatom(),
+                Decimal::permille(3),
+            )
+            .unwrap();
+        // floor(2_000_000 * 10_000 / 1_010_000) = 19801, of which ceil(59.403)
+        // is charged as fee
+        assert_eq!(
+            sim,
+            SwapSimulation {
+                offer: Asset::new(uosmo(), 10_000u128),
+                return_asset: Asset::new(uatom(), 19741u128),
+                fee: Asset::new(uatom(), 60u128),
+            }
+        );
+    }
+
+    #[test]
+    fn swapping_stable() {
+        let pool = stable_pool();
+        let sim = pool
+            .simulate_swap(
+                &Asset::new(uosmo(), 1_000_000u128),
+                &uatom(),
+                Decimal::zero(),
+            )
+            .unwrap();
+        // close to 1:1 in a balanced pool, but never more
+        assert!(sim.return_asset.amount < Uint128::new(1_000_000));
+        assert!(sim.return_asset.amount > Uint128::new(999_000));
+
+        // the stableswap curve has less slippage than the constant product
+        let xyk = Pool::xyk(pool.reserves.clone())
+            .simulate_swap(
+                &Asset::new(uosmo(), 1_000_000u128),
+                &uatom(),
+                Decimal::zero(),
+            )
+            .unwrap();
+        assert!(sim.return_asset.amount > xyk.return_asset.amount);
+    }
+
+    #[test_case(xyk_pool(), 19741 ; "xyk")]
+    #[test_case(xyk_pool(), 1 ; "xyk minimal amount")]
+    #[test_case(stable_pool(), 987_654 ; "stable")]
+    #[test_case(stable_pool(), 500_000_000 ; "stable large amount")]
+    fn reverse_simulating(pool: Pool, amount: u128) {
+        let ask = Asset::new(uatom(), amount);
+        let sim = pool
+            .reverse_simulate(&ask, &uosmo(), Decimal::permille(3))
+            .unwrap();
+        assert!(sim.return_asset.amount >= ask.amount);
+
+        // one unit less of the offer asset would not be enough
+        let offer = Asset::new(uosmo(), sim.offer.amount - Uint128::one());
+        let less = pool
+            .simulate_swap(&offer, &uatom(), Decimal::permille(3))
+            .unwrap();
+        assert!(less.return_asset.amount <= ask.amount);
+    }
+
+    #[test]
+    fn reverse_simulating_beyond_liquidity() {
+        assert_eq!(
+            xyk_pool().reverse_simulate(
+                &Asset::new(uatom(), 2_000_000u128),
+                &uosmo(),
+                Decimal::zero()
+            ),
+            Err(StdError::generic_err(
+                "insufficient liquidity: uatom:2000000"
+            ))
+        );
+    }
+
+    #[test]
+    fn swapping_invalid_assets() {
+        let pool = xyk_pool();
+        let mock_token = AssetInfo::cw20(Addr::unchecked("mock_token"));
+        assert_eq!(
+            pool.simulate_swap(&Asset::new(uosmo(), 1u128), &mock_token, Decimal::zero()),
+            Err(StdError::generic_err("asset not in pool: mock_token"))
+        );
+        assert_eq!(
+            pool.simulate_swap(&Asset::new(uosmo(), 1u128), &uosmo(), Decimal::zero()),
+            Err(StdError::generic_err("cannot swap uosmo for itself"))
+        );
+        assert!(pool
+            .simulate_swap(&Asset::new(uosmo(), 1u128), &uatom(), Decimal::one())
+            .is_err());
+    }
+
+    #[test]
+    fn providing_liquidity() {
+        let deposit = AssetList::try_from_assets(vec![
+            Asset::new(uosmo(), 1_000_000u128),
+            Asset::new(uatom(), 4_000_000u128),
+        ])
+        .unwrap();
+        assert_eq!(
+            xyk_pool()
+                .provide_liquidity_shares(&deposit, Uint128::zero())
+                .unwrap(),
+            Uint128::new(2_000_000)
+        );
+
+        // the excess of uatom does not mint shares
+        assert_eq!(
+            xyk_pool()
+                .provide_liquidity_shares(&deposit, Uint128::new(1_000_000))
+                .unwrap(),
+            Uint128::new(1_000_000)
+        );
+
+        // a proportional deposit into a stableswap pool mints proportional
+        // shares, less the unit deducted for rounding
+        let deposit = AssetList::try_from_assets(vec![
+            Asset::new(uosmo(), 1_000_000u128),
+            Asset::new(uatom(), 1_000_000u128),
+        ])
+        .unwrap();
+        assert_eq!(
+            stable_pool()
+                .provide_liquidity_shares(&deposit, Uint128::new(2_000_000_000))
+                .unwrap(),
+            Uint128::new(1_999_999)
+        );
+
+        // the first deposit must contain every asset of the pool
+        let deposit = AssetList::try_from_assets(vec![Asset::new(uosmo(), 1_000_000u128)]).unwrap();
+        let missing_uatom = || {
+            StdError::generic_err(
+                "the first deposit must contain every asset of the pool, missing uatom",
+            )
+        };
+        assert_eq!(
+            xyk_pool().provide_liquidity_shares(&deposit, Uint128::zero()),
+            Err(missing_uatom())
+        );
+        assert_eq!(
+            stable_pool().provide_liquidity_shares(&deposit, Uint128::zero()),
+            Err(missing_uatom())
+        );
+        let deposit = crate::AssetListBase(vec![
+            Asset::new(uosmo(), 1_000_000u128),
+            Asset::new(uatom(), 0u128),
+        ]);
+        assert_eq!(
+            stable_pool().provide_liquidity_shares(&deposit, Uint128::zero()),
+            Err(missing_uatom())
+        );
+
+        let deposit =
+            AssetList::try_from_assets(vec![Asset::cw20(Addr::unchecked("mock_token"), 1u128)])
+                .unwrap();
+        assert_eq!(
+            xyk_pool().provide_liquidity_shares(&deposit, Uint128::new(1_000_000)),
+            Err(StdError::generic_err("asset not in pool: mock_token"))
+        );
+    }
+
+    #[test]
+    fn withdrawing_liquidity() {
+        assert_eq!(
+            xyk_pool()
+                .withdraw_liquidity(Uint128::new(1), Uint128::new(3))
+                .unwrap(),
+            AssetList::try_from_assets(vec![
+                Asset::new(uosmo(), 333_333u128),
+                Asset::new(uatom(), 666_666u128),
+            ])
+            .unwrap()
+        );
+        assert_eq!(
+            xyk_pool().withdraw_liquidity(Uint128::new(4), Uint128::new(3)),
+            Err(StdError::generic_err("cannot withdraw 4 of 3 shares"))
+        );
+    }
+
+    /// A pool whose reserves are zero, as queried from a drained pool
+    ///
+    /// The list is built directly, as `AssetList::try_from_assets` purges zero
+    /// amounts.
+    fn empty_pool(curve: Curve) -> Pool {
+        Pool::new(
+            crate::AssetListBase(vec![Asset::new(uosmo(), 0u128), Asset::new(uatom(), 0u128)]),
+            curve,
+        )
+    }
+
+    #[test]
+    fn zero_reserves_and_supply() {
+        let zero_denominator = || StdError::generic_err("Denominator must not be zero");
+
+        assert_eq!(
+            empty_pool(Curve::Xyk).simulate_swap(
+                &Asset::new(uosmo(), 0u128),
+                &uatom(),
+                Decimal::zero()
+            ),
+            Err(zero_denominator())
+        );
+
+        let deposit = AssetList::try_from_assets(vec![
+            Asset::new(uosmo(), 1_000u128),
+            Asset::new(uatom(), 1_000u128),
+        ])
+        .unwrap();
+        assert_eq!(
+            empty_pool(Curve::Xyk).provide_liquidity_shares(&deposit, Uint128::new(1_000)),
+            Err(zero_denominator())
+        );
+        assert_eq!(
+            empty_pool(Curve::Stable { amp: 100 })
+                .provide_liquidity_shares(&deposit, Uint128::new(1_000)),
+            Err(zero_denominator())
+        );
+
+        assert_eq!(
+            xyk_pool().withdraw_liquidity(Uint128::zero(), Uint128::zero()),
+            Err(zero_denominator())
+        );
+    }
+}
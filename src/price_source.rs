@@ -0,0 +1,333 @@
+use std::collections::HashMap;
+
+use cosmwasm_std::{
+    from_json, to_json_binary, Addr, Decimal, StdError, StdResult, Uint128, Uint256,
+};
+
+use schemars::JsonSchema;
+use serde::{Deserialize, Serialize};
+
+use super::asset::Asset;
+use super::asset_info::{AssetInfo, AssetInfoUnchecked};
+use super::decimals::{Cw20DecimalsProvider, DecimalsProvider};
+use super::querier::AssetQuerier;
+
+/// A source of asset prices, denominated in a quote asset
+///
+/// Prices are per base unit of the asset and in base units of the quote asset,
+/// so that `amount * price` is the value of `amount` base units in base units
+/// of the quote asset.
+pub trait PriceSource {
+    /// Return the price of the asset
+    fn price(&self, querier: &dyn AssetQuerier, info: &AssetInfo) -> StdResult<Decimal>;
+
+    /// Return the value of the asset in base units of the quote asset, rounded
+    /// down
+    ///
+    /// Override this if prices per base unit can be too small to be
+    /// represented as a `Decimal`.
+    fn value(&self, querier: &dyn AssetQuerier, asset: &Asset) -> StdResult<Uint128> {
+        let price = self.price(querier, &asset.info)?;
+        asset
+            .amount
+            .checked_multiply_ratio(price.atomics(), Decimal::one().atomics())
+            .map_err(|err| StdError::generic_err(err.to_string()))
+    }
+}
+
+/// A price source that returns prices from a fixed map, e.g. for tests or for
+/// assets pegged to the quote asset
+#[derive(Clone, Debug, Default, PartialEq)]
+pub struct FixedPriceSource {
+    pub prices: HashMap<AssetInfo, Decimal>,
+}
+
+impl FixedPriceSource {
+    /// Create a new price source from the given asset prices
+    pub fn new<I: IntoIterator<Item = (AssetInfo, Decimal)>>(prices: I) -> Self {
+        Self {
+            prices: prices.into_iter().collect(),
+        }
+    }
+}
+
+impl PriceSource for FixedPriceSource {
+    fn price(&self, _querier: &dyn AssetQuerier, info: &AssetInfo) -> StdResult<Decimal> {
+        self.prices
+            .get(info)
+            .copied()
+            .ok_or_else(|| StdError::generic_err(format!("price not found: {}", info)))
+    }
+}
+
+/// The query message of an oracle contract used by `OraclePriceSource`
+#[derive(Serialize, Deserialize, Clone, Debug, PartialEq, Eq, JsonSchema)]
+#[serde(rename_all = "snake_case")]
+pub enum OracleQueryMsg {
+    /// Return the price of one whole unit of the asset in whole units of the
+    /// quote asset
+    Price { asset: AssetInfoUnchecked },
+}
+
+/// The response of the oracle contract to `OracleQueryMsg::Price`
+#[derive(Serialize, Deserialize, Clone, Debug, PartialEq, Eq, JsonSchema)]
+pub struct OraclePriceResponse {
+    pub price: Decimal,
+}
+
+/// A price source that queries an oracle contract
+///
+/// The oracle quotes prices in whole units, e.g. USD per OSMO, which are
+/// normalized to base units, e.g. uusd per uosmo, using the decimals of the
+/// asset and the quote asset. The decimals of the asset are read from the
+/// given `DecimalsProvider`.
+///
+/// Values are computed from the whole unit price, so they stay exact even if
+/// the price per base unit is too small to be represented as a `Decimal`, e.g.
+/// for 18 decimal tokens quoted in a 6 decimal asset.
+#[derive(Clone, Debug, PartialEq)]
+pub struct OraclePriceSource<D = Cw20DecimalsProvider> {
+    pub oracle: Addr,
+    pub quote_decimals: u8,
+    pub decimals: D,
+}
+
+impl<D> OraclePriceSource<D> {
+    /// Create a new price source for the given oracle contract
+    pub fn new(oracle: Addr, quote_decimals: u8, decimals: D) -> Self {
+        Self {
+            oracle,
+            quote_decimals,
+            decimals,
+        }
+    }
+}
+
+impl<D: DecimalsProvider> OraclePriceSource<D> {
+    /// Return the whole unit price of the asset and its decimals
+    fn whole_price(
+        &self,
+        querier: &dyn AssetQuerier,
+        info: &AssetInfo,
+    ) -> StdResult<(Decimal, u8)> {
+        let decimals = self.decimals.decimals(querier, info)?;
+        let msg = to_json_binary(&OracleQueryMsg::Price { asset: info.into() })?;
+        let res: OraclePriceResponse =
+            from_json(querier.query_contract(self.oracle.as_str(), &msg)?)?;
+        Ok((res.price, decimals))
+    }
+}
+
+impl<D: DecimalsProvider> PriceSource for OraclePriceSource<D> {
+    /// Return the price per base unit of the asset
+    ///
+    /// Returns an error if a nonzero price is too small to be represented per
+    /// base unit; `value` can still be used for such assets.
+    fn price(&self, querier: &dyn AssetQuerier, info: &AssetInfo) -> StdResult<Decimal> {
+        let (price, decimals) = self.whole_price(querier, info)?;
+        let atomics = Uint256::from(price.atomics())
+            .checked_multiply_ratio(pow10(self.quote_decimals.into())?, pow10(decimals.into())?)
+            .map_err(|err| StdError::generic_err(err.to_string()))?;
+        if atomics.is_zero() && !price.is_zero() {
+            return Err(StdError::generic_err(format!(
+                "price of {} is too small to be represented per base unit: {}",
+                info, price
+            )));
+        }
+        Ok(Decimal::new(Uint128::try_from(atomics)?))
+    }
+
+    fn value(&self, querier: &dyn AssetQuerier, asset: &Asset) -> StdResult<Uint128> {
+        let (price, decimals) = self.whole_price(querier, &asset.info)?;
+        let value = Uint256::from(asset.amount)
+            .checked_mul(Uint256::from(price.atomics()))?
+            .checked_multiply_ratio(
+                pow10(self.quote_decimals.into())?,
+                pow10(u32::from(decimals) + Decimal::DECIMAL_PLACES)?,
+            )
+            .map_err(|err| StdError::generic_err(err.to_string()))?;
+        Ok(Uint128::try_from(value)?)
+    }
+}
+
+fn pow10(exp: u32) -> StdResult<Uint256> {
+    Ok(Uint256::from(10u128).checked_pow(exp)?)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::{Asset, AssetList, CachedAssetQuerier};
+    use cosmwasm_std::testing::MockQuerier;
+    use cosmwasm_std::{
+        from_json, to_json_binary, ContractResult, QuerierWrapper, SystemResult, WasmQuery,
+    };
+    use cw20::{Cw20QueryMsg, TokenInfoResponse};
+    use std::str::FromStr;
+
+    fn mock_token() -> AssetInfo {
+        AssetInfo::cw20(Addr::unchecked("mock_token"))
+    }
+
+    fn mock_querier() -> MockQuerier {
+        let mut querier = MockQuerier::new(&[]);
+        querier.update_wasm(|query| {
+            let (contract_addr, msg) = match query {
+                WasmQuery::Smart { contract_addr, msg } => (contract_addr, msg),
+                _ => panic!("[mock]: unsupported wasm query {:?}", query),
+            };
+            let res = match contract_addr.as_str() {
+                "mock_token" => match from_json(msg).unwrap() {
+                    Cw20QueryMsg::TokenInfo {} => to_json_binary(&TokenInfoResponse {
+                        name: "Mock Token".to_string(),
+                        symbol: "MOCK".to_string(),
+                        decimals: 8,
+                        total_supply: Uint128::new(1_000_000),
+                    }),
+                    query => panic!("[mock]: unsupported cw20 query {:?}", query),
+                },
+                "mock_oracle" => match from_json(msg).unwrap() {
+                    OracleQueryMsg::Price { asset } => {
+                        let price = match asset.to_string().as_str() {
+                            "uosmo" => "0.5",
+                            "mock_token" => "20",
+                            "wei" => "0.0000000000005",
+                            _ => return SystemResult::Ok(ContractResult::Err("not found".into())),
+                        };
+                        to_json_binary(&OraclePriceResponse {
+                            price: Decimal::from_str(price).unwrap(),
+                        })
+                    }
+                },
+                _ => panic!("[mock]: unknown contract {}", contract_addr),
+            };
+            SystemResult::Ok(ContractResult::Ok(res.unwrap()))
+        });
+        querier
+    }
+
+    fn oracle() -> OraclePriceSource {
+        OraclePriceSource::new(
+            Addr::unchecked("mock_oracle"),
+            6,
+            Cw20DecimalsProvider::new(vec![
+                ("uosmo".to_string(), "OSMO".to_string(), 6),
+                ("uatom".to_string(), "ATOM".to_string(), 6),
+                ("wei".to_string(), "WEI".to_string(), 18),
+            ]),
+        )
+    }
+
+    #[test]
+    fn fixed_prices() {
+        let querier = MockQuerier::default();
+        let querier: QuerierWrapper = QuerierWrapper::new(&querier);
+
+        let source = FixedPriceSource::new(vec![
+            (AssetInfo::native("uosmo"), Decimal::percent(50)),
+            (mock_token(), Decimal::percent(200)),
+        ]);
+        assert_eq!(
+            source.price(&querier, &AssetInfo::native("uosmo")).unwrap(),
+            Decimal::percent(50)
+        );
+        assert_eq!(
+            source.price(&querier, &AssetInfo::native("uatom")),
+            Err(StdError::generic_err("price not found: uatom"))
+        );
+
+        let asset = Asset::native("uosmo", 12345u128);
+        assert_eq!(asset.value(&querier, &source).unwrap(), Uint128::new(6172));
+
+        let list = AssetList::try_from_assets(vec![
+            Asset::native("uosmo", 12345u128),
+            Asset::new(mock_token(), 100u128),
+        ])
+        .unwrap();
+        assert_eq!(
+            list.total_value(&querier, &source).unwrap(),
+            Uint128::new(6372)
+        );
+
+        let list = AssetList::try_from_assets(vec![Asset::native("uatom", 1u128)]).unwrap();
+        assert!(list.total_value(&querier, &source).is_err());
+    }
+
+    #[test]
+    fn oracle_prices() {
+        let querier = mock_querier();
+        let querier: QuerierWrapper = QuerierWrapper::new(&querier);
+        let source = oracle();
+
+        // 0.5 quote per osmo, both with 6 decimals
+        assert_eq!(
+            source.price(&querier, &AssetInfo::native("uosmo")).unwrap(),
+            Decimal::percent(50)
+        );
+        // 20 quote per token, with 6 and 8 decimals respectively
+        assert_eq!(
+            source.price(&querier, &mock_token()).unwrap(),
+            Decimal::percent(20)
+        );
+        // decimals of native coins must be provided
+        assert_eq!(
+            source.price(&querier, &AssetInfo::native("uluna")),
+            Err(StdError::generic_err("decimals not found: uluna"))
+        );
+        // the oracle does not have a price
+        assert!(source.price(&querier, &AssetInfo::native("uatom")).is_err());
+
+        let list = AssetList::try_from_assets(vec![
+            Asset::native("uosmo", 2_000_000u128),
+            Asset::new(mock_token(), 100_000_000u128),
+        ])
+        .unwrap();
+        assert_eq!(
+            list.total_value(&querier, &source).unwrap(),
+            Uint128::new(21_000_000)
+        );
+
+        // the sources work with any asset querier
+        let cached = CachedAssetQuerier::new(querier);
+        assert_eq!(
+            list.total_value(&cached, &source).unwrap(),
+            Uint128::new(21_000_000)
+        );
+    }
+
+    #[test]
+    fn oracle_prices_below_decimal_precision() {
+        let querier = mock_querier();
+        let querier: QuerierWrapper = QuerierWrapper::new(&querier);
+        let source = oracle();
+
+        // 5e-13 quote per whole token with 18 decimals is 5e-25 base units of
+        // the quote asset per wei, below the precision of `Decimal`
+        assert_eq!(
+            source.price(&querier, &AssetInfo::native("wei")),
+            Err(StdError::generic_err(
+                "price of wei is too small to be represented per base unit: 0.0000000000005"
+            ))
+        );
+
+        // 4e12 whole tokens are still worth 2 whole units of the quote asset
+        let asset = Asset::native("wei", 4_000_000_000_000_000_000_000_000_000_000u128);
+        assert_eq!(
+            asset.value(&querier, &source).unwrap(),
+            Uint128::new(2_000_000)
+        );
+        // one base unit of the quote asset is worth 2e24 wei, rounded down
+        assert_eq!(
+            Asset::native("wei", 2_000_000_000_000_000_000_000_000u128)
+                .value(&querier, &source)
+                .unwrap(),
+            Uint128::one()
+        );
+        assert_eq!(
+            Asset::native("wei", 1_999_999_999_999_999_999_999_999u128)
+                .value(&querier, &source)
+                .unwrap(),
+            Uint128::zero()
+        );
+    }
+}
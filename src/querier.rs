@@ -0,0 +1,610 @@
+use std::cell::RefCell;
+use std::collections::HashMap;
+
+use cosmwasm_std::{
+    to_json_vec, Binary, ContractResult, CustomQuery, Empty, QuerierWrapper, QueryRequest,
+    StdError, StdResult, SystemError, SystemResult, Uint128, WasmQuery,
+};
+use cw20::{BalanceResponse, Cw20QueryMsg, TokenInfoResponse};
+
+use schemars::JsonSchema;
+use serde::{Deserialize, Serialize};
+
+use super::asset_info::AssetInfo;
+use super::cw721::{ContractInfoResponse, Cw721QueryMsg, OwnerOfResponse};
+
+/// The metadata of an asset
+///
+/// NOTE: Native coins do not have on-chain metadata available to contracts, so
+/// the default querier uses the denom as name and symbol, and leaves the
+/// decimals unknown. CW721 NFTs use the name and symbol of their collection,
+/// and have 0 decimals.
+#[derive(Serialize, Deserialize, Clone, Debug, PartialEq, Eq, JsonSchema)]
+pub struct AssetMetadata {
+    pub name: String,
+    pub symbol: String,
+    pub decimals: Option<u8>,
+}
+
+/// A source of on-chain information about assets
+///
+/// Implemented for `QuerierWrapper<C>` with any custom query type `C`, using the
+/// bank module for native coins, the CW20 queries for CW20 tokens and the CW721
+/// queries for NFTs. Implement it to route the queries through chain-specific
+/// custom queries, or to cache the results.
+pub trait AssetQuerier {
+    /// Return the balance of the asset held by the address
+    ///
+    /// For CW721 NFTs this is 1 if the address owns the token, and 0 otherwise.
+    fn query_asset_balance(&self, info: &AssetInfo, address: &str) -> StdResult<Uint128>;
+
+    /// Return the total supply of the asset
+    ///
+    /// The supply of native coins is only available with the `native-supply`
+    /// feature, as the bank supply query requires the `cosmwasm_1_1`
+    /// capability of the chain.
+    fn query_asset_supply(&self, info: &AssetInfo) -> StdResult<Uint128>;
+
+    /// Return the metadata of the asset
+    fn query_asset_metadata(&self, info: &AssetInfo) -> StdResult<AssetMetadata>;
+
+    /// Return whether a contract exists at the address
+    ///
+    /// Returns `false` only if the chain reports that there is no such
+    /// contract; other failures of the query are returned as errors.
+    fn query_is_contract(&self, address: &str) -> StdResult<bool>;
+
+    /// Return the JSON response of a contract to the JSON query message
+    ///
+    /// Lets price sources and swap routers query oracles and pairs through a
+    /// `&dyn AssetQuerier`, which can not have generic methods.
+    fn query_contract(&self, contract_addr: &str, msg: &Binary) -> StdResult<Binary>;
+}
+
+impl<C: CustomQuery> AssetQuerier for QuerierWrapper<'_, C> {
+    fn query_asset_balance(&self, info: &AssetInfo, address: &str) -> StdResult<Uint128> {
+        match info {
+            AssetInfo::Cw20(contract_addr) => {
+                let res: BalanceResponse = self.query_wasm_smart(
+                    contract_addr,
+                    &Cw20QueryMsg::Balance {
+                        address: address.to_string(),
+                    },
+                )?;
+                Ok(res.balance)
+            }
+            AssetInfo::Native(denom) => Ok(self.query_balance(address, denom)?.amount),
+            AssetInfo::Cw721(contract_addr, token_id) => {
+                let res: OwnerOfResponse = self.query_wasm_smart(
+                    contract_addr,
+                    &Cw721QueryMsg::OwnerOf {
+                        token_id: token_id.clone(),
+                        include_expired: None,
+                    },
+                )?;
+                Ok(if res.owner == address {
+                    Uint128::one()
+                } else {
+                    Uint128::zero()
+                })
+            }
+        }
+    }
+
+    fn query_asset_supply(&self, info: &AssetInfo) -> StdResult<Uint128> {
+        match info {
+            AssetInfo::Cw20(contract_addr) => {
+                let res: TokenInfoResponse =
+                    self.query_wasm_smart(contract_addr, &Cw20QueryMsg::TokenInfo {})?;
+                Ok(res.total_supply)
+            }
+            #[cfg(feature = "native-supply")]
+            AssetInfo::Native(denom) => Ok(self.query_supply(denom)?.amount),
+            #[cfg(not(feature = "native-supply"))]
+            AssetInfo::Native(denom) => Err(cosmwasm_std::StdError::generic_err(format!(
+                "querying the supply of {} requires the `native-supply` feature",
+                denom
+            ))),
+            AssetInfo::Cw721(contract_addr, token_id) => {
+                // the query fails if the token does not exist
+                let _: OwnerOfResponse = self.query_wasm_smart(
+                    contract_addr,
+                    &Cw721QueryMsg::OwnerOf {
+                        token_id: token_id.clone(),
+                        include_expired: None,
+                    },
+                )?;
+                Ok(Uint128::one())
+            }
+        }
+    }
+
+    fn query_asset_metadata(&self, info: &AssetInfo) -> StdResult<AssetMetadata> {
+        match info {
+            AssetInfo::Cw20(contract_addr) => {
+                let res: TokenInfoResponse =
+                    self.query_wasm_smart(contract_addr, &Cw20QueryMsg::TokenInfo {})?;
+                Ok(AssetMetadata {
+                    name: res.name,
+                    symbol: res.symbol,
+                    decimals: Some(res.decimals),
+                })
+            }
+            AssetInfo::Native(denom) => Ok(AssetMetadata {
+                name: denom.clone(),
+                symbol: denom.clone(),
+                decimals: None,
+            }),
+            AssetInfo::Cw721(contract_addr, _) => {
+                let res: ContractInfoResponse =
+                    self.query_wasm_smart(contract_addr, &Cw721QueryMsg::ContractInfo {})?;
+                Ok(AssetMetadata {
+                    name: res.name,
+                    symbol: res.symbol,
+                    decimals: Some(0),
+                })
+            }
+        }
+    }
+
+    fn query_is_contract(&self, address: &str) -> StdResult<bool> {
+        // `QuerierWrapper::query_wasm_contract_info` turns every failure into a
+        // generic error, so the raw result is inspected instead
+        let request: QueryRequest<Empty> = WasmQuery::ContractInfo {
+            contract_addr: address.to_string(),
+        }
+        .into();
+        match self.raw_query(&to_json_vec(&request)?) {
+            SystemResult::Ok(ContractResult::Ok(_)) => Ok(true),
+            SystemResult::Err(SystemError::NoSuchContract { .. }) => Ok(false),
+            SystemResult::Err(err) => Err(StdError::generic_err(format!(
+                "Querier system error: {}",
+                err
+            ))),
+            SystemResult::Ok(ContractResult::Err(err)) => Err(StdError::generic_err(format!(
+                "Querier contract error: {}",
+                err
+            ))),
+        }
+    }
+
+    fn query_contract(&self, contract_addr: &str, msg: &Binary) -> StdResult<Binary> {
+        let request: QueryRequest<Empty> = WasmQuery::Smart {
+            contract_addr: contract_addr.to_string(),
+            msg: msg.clone(),
+        }
+        .into();
+        match self.raw_query(&to_json_vec(&request)?) {
+            SystemResult::Ok(ContractResult::Ok(res)) => Ok(res),
+            SystemResult::Err(err) => Err(StdError::generic_err(format!(
+                "Querier system error: {}",
+                err
+            ))),
+            SystemResult::Ok(ContractResult::Err(err)) => Err(StdError::generic_err(format!(
+                "Querier contract error: {}",
+                err
+            ))),
+        }
+    }
+}
+
+/// An asset querier that memoizes the results of another querier, meant to
+/// live for the duration of a single contract call
+///
+/// Native balances are fetched with one `BankQuery::AllBalances` query per
+/// address rather than one `BankQuery::Balance` query per denom. Balances are
+/// not invalidated by messages dispatched in the response, as those only execute
+/// after the call returns.
+pub struct CachedAssetQuerier<'a, C: CustomQuery = Empty> {
+    querier: QuerierWrapper<'a, C>,
+    native_balances: RefCell<HashMap<String, HashMap<String, Uint128>>>,
+    contract_balances: RefCell<HashMap<(AssetInfo, String), Uint128>>,
+    supplies: RefCell<HashMap<AssetInfo, Uint128>>,
+    metadata: RefCell<HashMap<AssetInfo, AssetMetadata>>,
+}
+
+impl<'a, C: CustomQuery> CachedAssetQuerier<'a, C> {
+    /// Create a new cached querier wrapping the given querier
+    pub fn new(querier: QuerierWrapper<'a, C>) -> Self {
+        Self {
+            querier,
+            native_balances: RefCell::default(),
+            contract_balances: RefCell::default(),
+            supplies: RefCell::default(),
+            metadata: RefCell::default(),
+        }
+    }
+}
+
+impl<C: CustomQuery> AssetQuerier for CachedAssetQuerier<'_, C> {
+    fn query_asset_balance(&self, info: &AssetInfo, address: &str) -> StdResult<Uint128> {
+        match info {
+            AssetInfo::Cw20(_) | AssetInfo::Cw721(..) => {
+                let key = (info.clone(), address.to_string());
+                if let Some(balance) = self.contract_balances.borrow().get(&key) {
+                    return Ok(*balance);
+                }
+                let balance = self.querier.query_asset_balance(info, address)?;
+                self.contract_balances.borrow_mut().insert(key, balance);
+                Ok(balance)
+            }
+            AssetInfo::Native(denom) => {
+                let mut native_balances = self.native_balances.borrow_mut();
+                let balances = match native_balances.get(address) {
+                    Some(balances) => balances,
+                    None => {
+                        let coins = self.querier.query_all_balances(address)?;
+                        native_balances.entry(address.to_string()).or_insert(
+                            coins
+                                .into_iter()
+                                .map(|coin| (coin.denom, coin.amount))
+                                .collect(),
+                        )
+                    }
+                };
+                Ok(balances.get(denom).copied().unwrap_or_default())
+            }
+        }
+    }
+
+    fn query_asset_supply(&self, info: &AssetInfo) -> StdResult<Uint128> {
+        if let Some(supply) = self.supplies.borrow().get(info) {
+            return Ok(*supply);
+        }
+        let supply = self.querier.query_asset_supply(info)?;
+        self.supplies.borrow_mut().insert(info.clone(), supply);
+        Ok(supply)
+    }
+
+    fn query_asset_metadata(&self, info: &AssetInfo) -> StdResult<AssetMetadata> {
+        if let Some(metadata) = self.metadata.borrow().get(info) {
+            return Ok(metadata.clone());
+        }
+        let metadata = self.querier.query_asset_metadata(info)?;
+        self.metadata
+            .borrow_mut()
+            .insert(info.clone(), metadata.clone());
+        Ok(metadata)
+    }
+
+    fn query_is_contract(&self, address: &str) -> StdResult<bool> {
+        self.querier.query_is_contract(address)
+    }
+
+    fn query_contract(&self, contract_addr: &str, msg: &Binary) -> StdResult<Binary> {
+        self.querier.query_contract(contract_addr, msg)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::testing::{contract_info_result, no_such_contract_result};
+    use crate::{Asset, AssetList};
+    use cosmwasm_std::testing::MockQuerier;
+    use cosmwasm_std::{
+        from_json, to_json_binary, Addr, Coin, ContractResult, Querier, QuerierResult,
+        SystemResult, WasmQuery,
+    };
+
+    fn mock_token() -> AssetInfo {
+        AssetInfo::cw20(Addr::unchecked("mock_token"))
+    }
+
+    fn mock_querier() -> MockQuerier {
+        let mut querier = MockQuerier::new(&[
+            ("alice", &[Coin::new(12345u128, "uosmo")]),
+            ("bob", &[Coin::new(67890u128, "uosmo")]),
+        ]);
+        querier.update_wasm(|query| match query {
+            WasmQuery::Smart { contract_addr, msg } if contract_addr == "mock_token" => {
+                let res = match from_json(msg).unwrap() {
+                    Cw20QueryMsg::Balance { address } => to_json_binary(&BalanceResponse {
+                        balance: Uint128::new(if address == "alice" { 88888 } else { 0 }),
+                    }),
+                    Cw20QueryMsg::TokenInfo {} => to_json_binary(&TokenInfoResponse {
+                        name: "Mock Token".to_string(),
+                        symbol: "MOCK".to_string(),
+                        decimals: 8,
+                        total_supply: Uint128::new(1_000_000),
+                    }),
+                    query => panic!("[mock]: unsupported cw20 query {:?}", query),
+                };
+                SystemResult::Ok(ContractResult::Ok(res.unwrap()))
+            }
+            WasmQuery::Smart { contract_addr, msg } if contract_addr == "mock_nft" => {
+                let res = match from_json(msg).unwrap() {
+                    Cw721QueryMsg::OwnerOf { token_id, .. } if token_id == "42" => {
+                        to_json_binary(&OwnerOfResponse {
+                            owner: "alice".to_string(),
+                        })
+                    }
+                    Cw721QueryMsg::OwnerOf { .. } => {
+                        return SystemResult::Ok(ContractResult::Err("token not found".into()))
+                    }
+                    Cw721QueryMsg::ContractInfo {} => to_json_binary(&ContractInfoResponse {
+                        name: "Mock NFT".to_string(),
+                        symbol: "MNFT".to_string(),
+                    }),
+                };
+                SystemResult::Ok(ContractResult::Ok(res.unwrap()))
+            }
+            _ => panic!("[mock]: unsupported wasm query {:?}", query),
+        });
+        querier
+    }
+
+    #[test]
+    fn querying_nft_ownership() {
+        let querier = mock_querier();
+        let querier: QuerierWrapper = QuerierWrapper::new(&querier);
+        let nft = AssetInfo::cw721(Addr::unchecked("mock_nft"), "42");
+
+        assert_eq!(
+            querier.query_asset_balance(&nft, "alice").unwrap(),
+            Uint128::one()
+        );
+        assert_eq!(
+            querier.query_asset_balance(&nft, "bob").unwrap(),
+            Uint128::zero()
+        );
+        assert_eq!(querier.query_asset_supply(&nft).unwrap(), Uint128::one());
+        assert!(querier
+            .query_asset_supply(&AssetInfo::cw721(Addr::unchecked("mock_nft"), "69"))
+            .is_err());
+        assert_eq!(
+            querier.query_asset_metadata(&nft).unwrap(),
+            AssetMetadata {
+                name: "Mock NFT".to_string(),
+                symbol: "MNFT".to_string(),
+                decimals: Some(0),
+            }
+        );
+    }
+
+    #[test]
+    fn querying_balances() {
+        let querier = mock_querier();
+        let querier: QuerierWrapper = QuerierWrapper::new(&querier);
+
+        assert_eq!(
+            querier
+                .query_asset_balance(&AssetInfo::native("uosmo"), "alice")
+                .unwrap(),
+            Uint128::new(12345)
+        );
+        assert_eq!(
+            querier
+                .query_asset_balance(&AssetInfo::native("uatom"), "alice")
+                .unwrap(),
+            Uint128::zero()
+        );
+        assert_eq!(
+            querier.query_asset_balance(&mock_token(), "alice").unwrap(),
+            Uint128::new(88888)
+        );
+    }
+
+    #[test]
+    fn querying_supply_and_metadata() {
+        let querier = mock_querier();
+        let querier: QuerierWrapper = QuerierWrapper::new(&querier);
+
+        #[cfg(feature = "native-supply")]
+        assert_eq!(
+            querier
+                .query_asset_supply(&AssetInfo::native("uosmo"))
+                .unwrap(),
+            Uint128::new(80235)
+        );
+        #[cfg(not(feature = "native-supply"))]
+        assert_eq!(
+            querier.query_asset_supply(&AssetInfo::native("uosmo")),
+            Err(cosmwasm_std::StdError::generic_err(
+                "querying the supply of uosmo requires the `native-supply` feature"
+            ))
+        );
+        assert_eq!(
+            querier.query_asset_supply(&mock_token()).unwrap(),
+            Uint128::new(1_000_000)
+        );
+
+        assert_eq!(
+            querier.query_asset_metadata(&mock_token()).unwrap(),
+            AssetMetadata {
+                name: "Mock Token".to_string(),
+                symbol: "MOCK".to_string(),
+                decimals: Some(8),
+            }
+        );
+        assert_eq!(
+            querier
+                .query_asset_metadata(&AssetInfo::native("uosmo"))
+                .unwrap(),
+            AssetMetadata {
+                name: "uosmo".to_string(),
+                symbol: "uosmo".to_string(),
+                decimals: None,
+            }
+        );
+    }
+
+    #[test]
+    fn querying_contracts() {
+        let mut querier: MockQuerier = MockQuerier::new(&[]);
+        querier.update_wasm(|query| match query {
+            WasmQuery::ContractInfo { contract_addr } if contract_addr == "mock_token" => {
+                contract_info_result()
+            }
+            WasmQuery::ContractInfo { contract_addr } if contract_addr == "alice" => {
+                no_such_contract_result(contract_addr)
+            }
+            _ => SystemResult::Err(SystemError::Unknown {}),
+        });
+        let querier: QuerierWrapper = QuerierWrapper::new(&querier);
+
+        assert_eq!(querier.query_is_contract("mock_token"), Ok(true));
+        assert_eq!(querier.query_is_contract("alice"), Ok(false));
+        assert!(querier.query_is_contract("uosmo").is_err());
+    }
+
+    #[test]
+    fn querying_contract_json() {
+        let querier = mock_querier();
+        let querier: QuerierWrapper = QuerierWrapper::new(&querier);
+
+        let res = querier
+            .query_contract(
+                "mock_token",
+                &to_json_binary(&Cw20QueryMsg::TokenInfo {}).unwrap(),
+            )
+            .unwrap();
+        let res: TokenInfoResponse = from_json(res).unwrap();
+        assert_eq!(res.total_supply, Uint128::new(1_000_000));
+
+        let msg = to_json_binary(&Cw721QueryMsg::OwnerOf {
+            token_id: "69".to_string(),
+            include_expired: None,
+        })
+        .unwrap();
+        assert_eq!(
+            querier.query_contract("mock_nft", &msg),
+            Err(StdError::generic_err(
+                "Querier contract error: token not found"
+            ))
+        );
+    }
+
+    struct FixedBalanceQuerier(Uint128);
+
+    impl AssetQuerier for FixedBalanceQuerier {
+        fn query_asset_balance(&self, _info: &AssetInfo, _address: &str) -> StdResult<Uint128> {
+            Ok(self.0)
+        }
+
+        fn query_asset_supply(&self, _info: &AssetInfo) -> StdResult<Uint128> {
+            Ok(self.0)
+        }
+
+        fn query_asset_metadata(&self, info: &AssetInfo) -> StdResult<AssetMetadata> {
+            Ok(AssetMetadata {
+                name: info.to_string(),
+                symbol: info.to_string(),
+                decimals: Some(6),
+            })
+        }
+
+        fn query_is_contract(&self, _address: &str) -> StdResult<bool> {
+            Ok(true)
+        }
+
+        fn query_contract(&self, contract_addr: &str, _msg: &Binary) -> StdResult<Binary> {
+            Err(StdError::generic_err(format!(
+                "unsupported query to {}",
+                contract_addr
+            )))
+        }
+    }
+
+    #[test]
+    fn custom_querier() {
+        let querier = FixedBalanceQuerier(Uint128::new(420));
+        let alice = Addr::unchecked("alice");
+
+        assert_eq!(
+            mock_token().query_balance(&querier, "alice").unwrap(),
+            Uint128::new(420)
+        );
+        let list = AssetList::query_asset_info_balances(
+            vec![AssetInfo::native("uosmo"), mock_token()],
+            &querier,
+            &alice,
+        )
+        .unwrap();
+        assert_eq!(
+            list,
+            AssetList::try_from_assets(vec![
+                Asset::native("uosmo", 420u128),
+                Asset::new(mock_token(), 420u128),
+            ])
+            .unwrap()
+        );
+    }
+
+    /// Counts the queries made to the wrapped mock querier
+    struct CountingQuerier {
+        querier: MockQuerier,
+        count: std::cell::Cell<usize>,
+    }
+
+    impl Querier for CountingQuerier {
+        fn raw_query(&self, bin_request: &[u8]) -> QuerierResult {
+            self.count.set(self.count.get() + 1);
+            self.querier.raw_query(bin_request)
+        }
+    }
+
+    #[test]
+    fn cached_querier() {
+        let querier = CountingQuerier {
+            querier: mock_querier(),
+            count: Default::default(),
+        };
+        let cached = CachedAssetQuerier::new(QuerierWrapper::<Empty>::new(&querier));
+        let alice = Addr::unchecked("alice");
+
+        let infos = vec![
+            AssetInfo::native("uosmo"),
+            AssetInfo::native("uatom"),
+            mock_token(),
+        ];
+        let expected = AssetList::try_from_assets(vec![
+            Asset::native("uosmo", 12345u128),
+            Asset::new(mock_token(), 88888u128),
+        ])
+        .unwrap();
+
+        // one AllBalances query for the native coins, one Balance query for the token
+        let list = AssetList::query_asset_info_balances(infos.clone(), &cached, &alice).unwrap();
+        assert_eq!(list, expected);
+        assert_eq!(querier.count.get(), 2);
+
+        // served from the cache
+        let list = AssetList::query_asset_info_balances(infos, &cached, &alice).unwrap();
+        assert_eq!(list, expected);
+        assert_eq!(
+            cached
+                .query_asset_balance(&AssetInfo::native("uosmo"), "alice")
+                .unwrap(),
+            Uint128::new(12345)
+        );
+        assert_eq!(querier.count.get(), 2);
+
+        // other addresses are queried separately
+        assert_eq!(
+            cached
+                .query_asset_balance(&AssetInfo::native("uosmo"), "bob")
+                .unwrap(),
+            Uint128::new(67890)
+        );
+        assert_eq!(querier.count.get(), 3);
+
+        assert_eq!(
+            cached.query_asset_supply(&mock_token()).unwrap(),
+            Uint128::new(1_000_000)
+        );
+        assert_eq!(
+            cached.query_asset_metadata(&mock_token()).unwrap().decimals,
+            Some(8)
+        );
+        assert_eq!(
+            cached.query_asset_supply(&mock_token()).unwrap(),
+            Uint128::new(1_000_000)
+        );
+        assert_eq!(
+            cached.query_asset_metadata(&mock_token()).unwrap().decimals,
+            Some(8)
+        );
+        assert_eq!(querier.count.get(), 5);
+    }
+}
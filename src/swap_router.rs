@@ -0,0 +1,547 @@
+use std::collections::HashMap;
+
+use cosmwasm_std::{
+    from_json, to_json_binary, Addr, CosmosMsg, Decimal, StdError, StdResult, Uint128, WasmMsg,
+};
+
+use schemars::JsonSchema;
+use serde::{Deserialize, Serialize};
+
+use super::asset::Asset;
+use super::asset_info::AssetInfo;
+use super::asset_list::AssetList;
+use super::querier::AssetQuerier;
+
+/// A router that swaps assets into a target asset, e.g. to sell the rewards of
+/// an auto-compounding strategy
+///
+/// Every asset in the offer is swapped separately. Assets of the ask asset
+/// itself are left untouched and are not counted towards the amount received.
+pub trait SwapRouter {
+    /// Generate messages that swap every asset in `offer` for `ask`
+    ///
+    /// `min_outs` pairs offer assets with the minimum amount of `ask` to
+    /// receive from swapping them, as separate swaps can not enforce a minimum
+    /// on their total. Swaps of offer assets without an entry have no minimum.
+    fn swap_msgs(
+        &self,
+        offer: &AssetList,
+        ask: &AssetInfo,
+        min_outs: &[(AssetInfo, Uint128)],
+    ) -> StdResult<Vec<CosmosMsg>>;
+
+    /// Return the amount of `ask` received when swapping every asset in
+    /// `offer`
+    fn simulate_swap(
+        &self,
+        querier: &dyn AssetQuerier,
+        offer: &AssetList,
+        ask: &AssetInfo,
+    ) -> StdResult<Uint128>;
+}
+
+/// Return the assets in the offer that need to be swapped for the ask asset,
+/// together with the minimum amount to receive from each swap
+///
+/// Returns an error if a minimum is given for an asset that is not swapped.
+pub(crate) fn swaps_to_make<'a>(
+    offer: &'a AssetList,
+    ask: &AssetInfo,
+    min_outs: &[(AssetInfo, Uint128)],
+) -> StdResult<Vec<(&'a Asset, Uint128)>> {
+    let assets: Vec<&Asset> = offer
+        .into_iter()
+        .filter(|asset| asset.info != *ask && !asset.amount.is_zero())
+        .collect();
+    if let Some((info, _)) = min_outs
+        .iter()
+        .find(|(info, _)| !assets.iter().any(|asset| asset.info == *info))
+    {
+        return Err(StdError::generic_err(format!(
+            "no {} to swap for {}",
+            info, ask
+        )));
+    }
+    Ok(assets
+        .into_iter()
+        .map(|asset| {
+            let min_out = min_outs
+                .iter()
+                .find(|(info, _)| *info == asset.info)
+                .map(|(_, min_out)| *min_out)
+                .unwrap_or_default();
+            (asset, min_out)
+        })
+        .collect())
+}
+
+/// An asset info in the format of Terraswap and Astroport pair messages
+#[derive(Serialize, Deserialize, Clone, Debug, PartialEq, Eq, JsonSchema)]
+#[serde(rename_all = "snake_case")]
+pub enum PairAssetInfo {
+    Token { contract_addr: String },
+    NativeToken { denom: String },
+}
+
+/// An asset in the format of Terraswap and Astroport pair messages
+#[derive(Serialize, Deserialize, Clone, Debug, PartialEq, Eq, JsonSchema)]
+pub struct PairAsset {
+    pub info: PairAssetInfo,
+    pub amount: Uint128,
+}
+
+impl TryFrom<&Asset> for PairAsset {
+    type Error = StdError;
+
+    fn try_from(asset: &Asset) -> StdResult<Self> {
+        let info = match &asset.info {
+            AssetInfo::Native(denom) => PairAssetInfo::NativeToken {
+                denom: denom.clone(),
+            },
+            AssetInfo::Cw20(contract_addr) => PairAssetInfo::Token {
+                contract_addr: contract_addr.to_string(),
+            },
+            AssetInfo::Cw721(..) => {
+                return Err(StdError::generic_err("cw721 tokens can not be swapped"))
+            }
+        };
+        Ok(Self {
+            info,
+            amount: asset.amount,
+        })
+    }
+}
+
+/// The execute message of a Terraswap or Astroport pair contract used by
+/// `PairRouter`
+#[derive(Serialize, Deserialize, Clone, Debug, PartialEq, Eq, JsonSchema)]
+#[serde(rename_all = "snake_case")]
+pub enum PairExecuteMsg {
+    /// Swap the offer asset, sent along as native coins, for the other asset
+    /// of the pair
+    ///
+    /// The pair fails the swap if the amount returned before its commission is
+    /// less than `offer_asset.amount / belief_price` by more than `max_spread`.
+    Swap {
+        offer_asset: PairAsset,
+        belief_price: Option<Decimal>,
+        max_spread: Option<Decimal>,
+        to: Option<String>,
+    },
+}
+
+/// The message sent to a pair contract along with CW20 tokens to swap them
+#[derive(Serialize, Deserialize, Clone, Debug, PartialEq, Eq, JsonSchema)]
+#[serde(rename_all = "snake_case")]
+pub enum PairCw20HookMsg {
+    /// Swap the sent tokens for the other asset of the pair, like
+    /// `PairExecuteMsg::Swap`
+    Swap {
+        belief_price: Option<Decimal>,
+        max_spread: Option<Decimal>,
+        to: Option<String>,
+    },
+}
+
+/// The query message of a Terraswap or Astroport pair contract used by
+/// `PairRouter`
+#[derive(Serialize, Deserialize, Clone, Debug, PartialEq, Eq, JsonSchema)]
+#[serde(rename_all = "snake_case")]
+pub enum PairQueryMsg {
+    /// Return the amount of the other asset of the pair returned for the offer
+    /// asset
+    Simulation { offer_asset: PairAsset },
+}
+
+/// The response of the pair contract to `PairQueryMsg::Simulation`
+#[derive(Serialize, Deserialize, Clone, Debug, PartialEq, Eq, JsonSchema)]
+pub struct SimulationResponse {
+    /// The amount returned, after the commission
+    pub return_amount: Uint128,
+    pub spread_amount: Uint128,
+    pub commission_amount: Uint128,
+}
+
+/// A router that swaps through a single Terraswap or Astroport style pair
+/// contract
+///
+/// A pair swaps the offer asset for its other asset, so the ask asset must be
+/// the other asset of the pair. Minimum amounts are enforced through the
+/// pair's belief price with zero max spread, so they bound the amount returned
+/// before the pair's commission.
+#[derive(Clone, Debug, PartialEq, Eq)]
+pub struct PairRouter {
+    pub pair: Addr,
+}
+
+impl PairRouter {
+    /// Create a new router for the given pair contract
+    pub fn new(pair: Addr) -> Self {
+        Self { pair }
+    }
+}
+
+impl SwapRouter for PairRouter {
+    fn swap_msgs(
+        &self,
+        offer: &AssetList,
+        ask: &AssetInfo,
+        min_outs: &[(AssetInfo, Uint128)],
+    ) -> StdResult<Vec<CosmosMsg>> {
+        swaps_to_make(offer, ask, min_outs)?
+            .into_iter()
+            .map(|(asset, min_out)| {
+                let offer_asset = PairAsset::try_from(asset)?;
+                let (belief_price, max_spread) = if min_out.is_zero() {
+                    (None, None)
+                } else {
+                    let belief_price = Decimal::checked_from_ratio(asset.amount, min_out)
+                        .map_err(|err| StdError::generic_err(err.to_string()))?;
+                    (Some(belief_price), Some(Decimal::zero()))
+                };
+                match &asset.info {
+                    AssetInfo::Cw20(_) => asset.send_msg(
+                        &self.pair,
+                        to_json_binary(&PairCw20HookMsg::Swap {
+                            belief_price,
+                            max_spread,
+                            to: None,
+                        })?,
+                    ),
+                    _ => Ok(CosmosMsg::Wasm(WasmMsg::Execute {
+                        contract_addr: self.pair.to_string(),
+                        msg: to_json_binary(&PairExecuteMsg::Swap {
+                            offer_asset,
+                            belief_price,
+                            max_spread,
+                            to: None,
+                        })?,
+                        funds: vec![asset.try_into()?],
+                    })),
+                }
+            })
+            .collect()
+    }
+
+    fn simulate_swap(
+        &self,
+        querier: &dyn AssetQuerier,
+        offer: &AssetList,
+        ask: &AssetInfo,
+    ) -> StdResult<Uint128> {
+        swaps_to_make(offer, ask, &[])?.into_iter().try_fold(
+            Uint128::zero(),
+            |total, (asset, _)| {
+                let msg = to_json_binary(&PairQueryMsg::Simulation {
+                    offer_asset: asset.try_into()?,
+                })?;
+                let res: SimulationResponse =
+                    from_json(querier.query_contract(self.pair.as_str(), &msg)?)?;
+                Ok(total.checked_add(res.return_amount)?)
+            },
+        )
+    }
+}
+
+/// An in-memory router swapping at fixed rates, to test code using a
+/// `SwapRouter` without any contracts
+///
+/// The generated messages are `PairExecuteMsg::Swap` messages to the router's
+/// address, like those of `PairRouter`, so they can be asserted on. Unlike a
+/// real router, `swap_msgs` already fails if a swap would return less than
+/// `min_out`.
+#[derive(Clone, Debug, PartialEq)]
+pub struct MockSwapRouter {
+    pub router: PairRouter,
+    /// The amount of the ask asset received per unit of the offer asset, keyed
+    /// by `(offer, ask)`
+    pub rates: HashMap<(AssetInfo, AssetInfo), Decimal>,
+}
+
+impl MockSwapRouter {
+    /// Create a new mock router at the given address with the given rates
+    pub fn new<I: IntoIterator<Item = ((AssetInfo, AssetInfo), Decimal)>>(
+        address: Addr,
+        rates: I,
+    ) -> Self {
+        Self {
+            router: PairRouter::new(address),
+            rates: rates.into_iter().collect(),
+        }
+    }
+
+    fn return_amount(&self, asset: &Asset, ask: &AssetInfo) -> StdResult<Uint128> {
+        let rate = self
+            .rates
+            .get(&(asset.info.clone(), ask.clone()))
+            .ok_or_else(|| {
+                StdError::generic_err(format!("no swap route from {} to {}", asset.info, ask))
+            })?;
+        asset
+            .amount
+            .checked_multiply_ratio(rate.atomics(), Decimal::one().atomics())
+            .map_err(|err| StdError::generic_err(err.to_string()))
+    }
+}
+
+impl SwapRouter for MockSwapRouter {
+    fn swap_msgs(
+        &self,
+        offer: &AssetList,
+        ask: &AssetInfo,
+        min_outs: &[(AssetInfo, Uint128)],
+    ) -> StdResult<Vec<CosmosMsg>> {
+        for (asset, min_out) in swaps_to_make(offer, ask, min_outs)? {
+            let return_amount = self.return_amount(asset, ask)?;
+            if return_amount < min_out {
+                return Err(StdError::generic_err(format!(
+                    "swap returns less than min_out: {} < {}",
+                    return_amount, min_out
+                )));
+            }
+        }
+        self.router.swap_msgs(offer, ask, min_outs)
+    }
+
+    fn simulate_swap(
+        &self,
+        _querier: &dyn AssetQuerier,
+        offer: &AssetList,
+        ask: &AssetInfo,
+    ) -> StdResult<Uint128> {
+        swaps_to_make(offer, ask, &[])?
+            .into_iter()
+            .try_fold(Uint128::zero(), |total, (asset, _)| {
+                Ok(total.checked_add(self.return_amount(asset, ask)?)?)
+            })
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use cosmwasm_std::testing::MockQuerier;
+    use cosmwasm_std::{Coin, ContractResult, QuerierWrapper, SystemResult, WasmQuery};
+    use cw20::Cw20ExecuteMsg;
+
+    fn mock_token() -> AssetInfo {
+        AssetInfo::cw20(Addr::unchecked("mock_token"))
+    }
+
+    fn mock_router() -> MockSwapRouter {
+        MockSwapRouter::new(
+            Addr::unchecked("router"),
+            [
+                (
+                    (AssetInfo::native("uosmo"), AssetInfo::native("uatom")),
+                    Decimal::percent(10),
+                ),
+                (
+                    (mock_token(), AssetInfo::native("uatom")),
+                    Decimal::percent(200),
+                ),
+            ],
+        )
+    }
+
+    fn mock_offer() -> AssetList {
+        AssetList::try_from_assets(vec![
+            Asset::native("uosmo", 12345u128),
+            Asset::new(mock_token(), 100u128),
+            Asset::native("uatom", 5u128),
+        ])
+        .unwrap()
+    }
+
+    #[test]
+    fn selecting_swaps() {
+        let offer = mock_offer();
+        let uatom = AssetInfo::native("uatom");
+
+        assert_eq!(
+            swaps_to_make(&offer, &uatom, &[]).unwrap(),
+            vec![
+                (&Asset::native("uosmo", 12345u128), Uint128::zero()),
+                (&Asset::new(mock_token(), 100u128), Uint128::zero()),
+            ]
+        );
+        assert_eq!(
+            swaps_to_make(
+                &offer,
+                &uatom,
+                &[
+                    (AssetInfo::native("uosmo"), Uint128::new(1000)),
+                    (mock_token(), Uint128::one()),
+                ]
+            )
+            .unwrap(),
+            vec![
+                (&Asset::native("uosmo", 12345u128), Uint128::new(1000)),
+                (&Asset::new(mock_token(), 100u128), Uint128::one()),
+            ]
+        );
+        assert_eq!(
+            swaps_to_make(&offer, &uatom, &[(mock_token(), Uint128::one())]).unwrap(),
+            vec![
+                (&Asset::native("uosmo", 12345u128), Uint128::zero()),
+                (&Asset::new(mock_token(), 100u128), Uint128::one()),
+            ]
+        );
+
+        let offer = AssetList::try_from_assets(vec![Asset::native("uatom", 5u128)]).unwrap();
+        assert_eq!(swaps_to_make(&offer, &uatom, &[]).unwrap(), vec![]);
+        assert_eq!(
+            swaps_to_make(&offer, &uatom, &[(uatom.clone(), Uint128::one())]),
+            Err(StdError::generic_err("no uatom to swap for uatom"))
+        );
+        assert_eq!(
+            swaps_to_make(
+                &offer,
+                &uatom,
+                &[(AssetInfo::native("uosmo"), Uint128::one())]
+            ),
+            Err(StdError::generic_err("no uosmo to swap for uatom"))
+        );
+    }
+
+    #[test]
+    fn swapping_through_pair() {
+        let router = PairRouter::new(Addr::unchecked("pair"));
+        let uatom = AssetInfo::native("uatom");
+
+        let msgs = router.swap_msgs(&mock_offer(), &uatom, &[]).unwrap();
+        assert_eq!(
+            msgs,
+            vec![
+                CosmosMsg::Wasm(WasmMsg::Execute {
+                    contract_addr: "pair".to_string(),
+                    msg: to_json_binary(&PairExecuteMsg::Swap {
+                        offer_asset: PairAsset {
+                            info: PairAssetInfo::NativeToken {
+                                denom: "uosmo".to_string(),
+                            },
+                            amount: Uint128::new(12345),
+                        },
+                        belief_price: None,
+                        max_spread: None,
+                        to: None,
+                    })
+                    .unwrap(),
+                    funds: vec![Coin::new(12345u128, "uosmo")],
+                }),
+                CosmosMsg::Wasm(WasmMsg::Execute {
+                    contract_addr: "mock_token".to_string(),
+                    msg: to_json_binary(&Cw20ExecuteMsg::Send {
+                        contract: "pair".to_string(),
+                        amount: Uint128::new(100),
+                        msg: to_json_binary(&PairCw20HookMsg::Swap {
+                            belief_price: None,
+                            max_spread: None,
+                            to: None,
+                        })
+                        .unwrap(),
+                    })
+                    .unwrap(),
+                    funds: vec![],
+                }),
+            ]
+        );
+
+        let msgs = router
+            .swap_msgs(&mock_offer(), &uatom, &[(mock_token(), Uint128::new(400))])
+            .unwrap();
+        assert_eq!(
+            msgs[1],
+            Asset::new(mock_token(), 100u128)
+                .send_msg(
+                    "pair",
+                    to_json_binary(&PairCw20HookMsg::Swap {
+                        belief_price: Some(Decimal::percent(25)),
+                        max_spread: Some(Decimal::zero()),
+                        to: None,
+                    })
+                    .unwrap()
+                )
+                .unwrap()
+        );
+
+        let offer =
+            AssetList::try_from_assets(vec![Asset::cw721(Addr::unchecked("mock_nft"), "1")])
+                .unwrap();
+        assert_eq!(
+            router.swap_msgs(&offer, &uatom, &[]),
+            Err(StdError::generic_err("cw721 tokens can not be swapped"))
+        );
+    }
+
+    #[test]
+    fn simulating_through_pair() {
+        let mut querier: MockQuerier = MockQuerier::new(&[]);
+        querier.update_wasm(|query| match query {
+            WasmQuery::Smart { contract_addr, msg } if contract_addr == "pair" => {
+                let res = match from_json(msg).unwrap() {
+                    PairQueryMsg::Simulation { offer_asset } => {
+                        to_json_binary(&SimulationResponse {
+                            return_amount: offer_asset.amount * Uint128::new(2),
+                            spread_amount: Uint128::zero(),
+                            commission_amount: Uint128::zero(),
+                        })
+                    }
+                };
+                SystemResult::Ok(ContractResult::Ok(res.unwrap()))
+            }
+            _ => panic!("[mock]: unsupported wasm query {:?}", query),
+        });
+        let querier: QuerierWrapper = QuerierWrapper::new(&querier);
+
+        let router: Box<dyn SwapRouter> = Box::new(PairRouter::new(Addr::unchecked("pair")));
+        assert_eq!(
+            router
+                .simulate_swap(&querier, &mock_offer(), &AssetInfo::native("uatom"))
+                .unwrap(),
+            Uint128::new(24890)
+        );
+    }
+
+    #[test]
+    fn swapping_through_mock_router() {
+        let router = mock_router();
+        let querier: MockQuerier = MockQuerier::new(&[]);
+        let querier: QuerierWrapper = QuerierWrapper::new(&querier);
+        let uatom = AssetInfo::native("uatom");
+
+        assert_eq!(
+            router
+                .simulate_swap(&querier, &mock_offer(), &uatom)
+                .unwrap(),
+            Uint128::new(1234 + 200)
+        );
+        assert_eq!(
+            router.swap_msgs(&mock_offer(), &uatom, &[]).unwrap(),
+            router.router.swap_msgs(&mock_offer(), &uatom, &[]).unwrap()
+        );
+
+        let uosmo = AssetInfo::native("uosmo");
+        assert!(router
+            .swap_msgs(
+                &mock_offer(),
+                &uatom,
+                &[
+                    (uosmo.clone(), Uint128::new(1234)),
+                    (mock_token(), Uint128::new(200))
+                ]
+            )
+            .is_ok());
+        assert_eq!(
+            router.swap_msgs(&mock_offer(), &uatom, &[(uosmo, Uint128::new(1235))]),
+            Err(StdError::generic_err(
+                "swap returns less than min_out: 1234 < 1235"
+            ))
+        );
+
+        assert_eq!(
+            router.simulate_swap(&querier, &mock_offer(), &AssetInfo::native("uusd")),
+            Err(StdError::generic_err("no swap route from uosmo to uusd"))
+        );
+    }
+}
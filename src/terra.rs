@@ -0,0 +1,250 @@
+//! Treasury queries and tax helpers for Terra Classic
+//!
+//! NOTE: The query types mirror those of `terra-cosmwasm`, which is built
+//! against cosmwasm-std 0.16 and can therefore not be used together with this
+//! crate.
+
+use cosmwasm_std::{
+    Coin, CosmosMsg, CustomQuery, Decimal, QuerierWrapper, QueryRequest, StdResult, Uint128,
+};
+
+use schemars::JsonSchema;
+use serde::{Deserialize, Serialize};
+
+use crate::{Asset, AssetInfo, AssetList};
+
+/// An override of `QueryRequest::Custom` to access Terra-specific modules
+#[derive(Serialize, Deserialize, Clone, Debug, PartialEq, JsonSchema)]
+#[serde(rename_all = "snake_case")]
+pub struct TerraQueryWrapper {
+    pub route: TerraRoute,
+    pub query_data: TerraQuery,
+}
+
+impl CustomQuery for TerraQueryWrapper {}
+
+/// The module a Terra-specific query is routed to
+#[derive(Serialize, Deserialize, Clone, Debug, PartialEq, JsonSchema)]
+#[serde(rename_all = "snake_case")]
+pub enum TerraRoute {
+    Market,
+    Treasury,
+    Oracle,
+    Wasm,
+}
+
+/// The Terra-specific queries
+#[derive(Serialize, Deserialize, Clone, Debug, PartialEq, JsonSchema)]
+#[serde(rename_all = "snake_case")]
+pub enum TerraQuery {
+    Swap {
+        offer_coin: Coin,
+        ask_denom: String,
+    },
+    TaxRate {},
+    TaxCap {
+        denom: String,
+    },
+    ExchangeRates {
+        base_denom: String,
+        quote_denoms: Vec<String>,
+    },
+    ContractInfo {
+        contract_address: String,
+    },
+}
+
+/// The response to `TerraQuery::TaxRate`
+#[derive(Serialize, Deserialize, Clone, Debug, PartialEq, JsonSchema)]
+pub struct TaxRateResponse {
+    pub rate: Decimal,
+}
+
+/// The response to `TerraQuery::TaxCap`
+#[derive(Serialize, Deserialize, Clone, Debug, PartialEq, JsonSchema)]
+pub struct TaxCapResponse {
+    pub cap: Uint128,
+}
+
+fn query_treasury<C: CustomQuery, T: serde::de::DeserializeOwned>(
+    querier: &QuerierWrapper<C>,
+    query_data: TerraQuery,
+) -> StdResult<T> {
+    let request = QueryRequest::Custom(TerraQueryWrapper {
+        route: TerraRoute::Treasury,
+        query_data,
+    });
+    QuerierWrapper::<TerraQueryWrapper>::new(&**querier).query(&request)
+}
+
+/// Query the current tax rate from the treasury module
+pub fn query_tax_rate<C: CustomQuery>(querier: &QuerierWrapper<C>) -> StdResult<Decimal> {
+    let res: TaxRateResponse = query_treasury(querier, TerraQuery::TaxRate {})?;
+    Ok(res.rate)
+}
+
+/// Query the tax cap of a native coin from the treasury module
+pub fn query_tax_cap<C: CustomQuery, A: Into<String>>(
+    querier: &QuerierWrapper<C>,
+    denom: A,
+) -> StdResult<Uint128> {
+    let res: TaxCapResponse = query_treasury(
+        querier,
+        TerraQuery::TaxCap {
+            denom: denom.into(),
+        },
+    )?;
+    Ok(res.cap)
+}
+
+impl Asset {
+    /// Compute the tax charged when transferring the asset
+    ///
+    /// The tax is charged on top of the amount sent, so this returns the part
+    /// of the asset's amount that goes to the tax if the whole amount is spent,
+    /// i.e. `amount - amount / (1 + tax_rate)`, capped at the denom's tax cap.
+    /// CW20 tokens and CW721 NFTs are not taxed.
+    pub fn compute_tax<C: CustomQuery>(&self, querier: &QuerierWrapper<C>) -> StdResult<Uint128> {
+        match &self.info {
+            AssetInfo::Native(denom) => {
+                let tax_rate = query_tax_rate(querier)?;
+                let tax_cap = query_tax_cap(querier, denom)?;
+                let net = self.amount.multiply_ratio(
+                    Decimal::one().atomics(),
+                    (Decimal::one() + tax_rate).atomics(),
+                );
+                Ok(std::cmp::min(self.amount.checked_sub(net)?, tax_cap))
+            }
+            AssetInfo::Cw20(_) | AssetInfo::Cw721(..) => Ok(Uint128::zero()),
+        }
+    }
+
+    /// Return a copy of the asset with the tax deducted from the amount, i.e.
+    /// the amount that can be sent such that the amount plus tax equals the
+    /// asset's amount
+    pub fn deduct_tax<C: CustomQuery>(&self, querier: &QuerierWrapper<C>) -> StdResult<Self> {
+        let tax = self.compute_tax(querier)?;
+        Ok(Asset::new(self.info.clone(), self.amount.checked_sub(tax)?))
+    }
+}
+
+impl AssetList {
+    /// Generate transfer messages for every asset in the list, with the tax
+    /// deducted from the amount of each native coin
+    pub fn transfer_msgs_after_tax<A: Into<String> + Clone, C: CustomQuery>(
+        &self,
+        querier: &QuerierWrapper<C>,
+        to: A,
+    ) -> StdResult<Vec<CosmosMsg>> {
+        self.transfer_msgs_after_tax_custom(querier, to)
+    }
+
+    /// Like `transfer_msgs_after_tax`, but for a contract using custom messages
+    pub fn transfer_msgs_after_tax_custom<A: Into<String> + Clone, C: CustomQuery, T>(
+        &self,
+        querier: &QuerierWrapper<C>,
+        to: A,
+    ) -> StdResult<Vec<CosmosMsg<T>>> {
+        self.iter()
+            .map(|asset| asset.deduct_tax(querier)?.transfer_msg_custom(to.clone()))
+            .collect()
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::testing::mock_dependencies;
+    use cosmwasm_std::{Addr, BankMsg};
+
+    #[test]
+    fn computing_tax() {
+        let mut deps = mock_dependencies();
+        deps.querier.set_native_tax_rate(Decimal::permille(1));
+        deps.querier.set_native_tax_cap("uusd", 1_000_000);
+        let querier = deps.as_ref().querier;
+
+        let asset = Asset::native("uusd", 1_001_000u128);
+        assert_eq!(asset.compute_tax(&querier).unwrap(), Uint128::new(1000));
+        assert_eq!(
+            asset.deduct_tax(&querier).unwrap(),
+            Asset::native("uusd", 1_000_000u128)
+        );
+
+        // the tax is capped
+        let asset = Asset::native("uusd", 10_000_000_000u128);
+        assert_eq!(
+            asset.compute_tax(&querier).unwrap(),
+            Uint128::new(1_000_000)
+        );
+
+        // cw20 tokens are not taxed
+        let asset = Asset::cw20(Addr::unchecked("mock_token"), 1_001_000u128);
+        assert_eq!(asset.compute_tax(&querier).unwrap(), Uint128::zero());
+
+        // the tax cap must be set
+        let asset = Asset::native("uluna", 1_001_000u128);
+        assert!(asset.compute_tax(&querier).is_err());
+    }
+
+    #[test]
+    fn transferring_after_tax() {
+        let mut deps = mock_dependencies();
+        deps.querier.set_native_tax_rate(Decimal::permille(1));
+        deps.querier.set_native_tax_cap("uusd", 1_000_000);
+        let querier = deps.as_ref().querier;
+
+        let list = AssetList::try_from_assets(vec![
+            Asset::native("uusd", 1_001_000u128),
+            Asset::cw20(Addr::unchecked("mock_token"), 88888u128),
+        ])
+        .unwrap();
+        let msgs = list.transfer_msgs_after_tax(&querier, "alice").unwrap();
+        assert_eq!(
+            msgs,
+            vec![
+                CosmosMsg::Bank(BankMsg::Send {
+                    to_address: "alice".to_string(),
+                    amount: vec![Coin::new(1_000_000u128, "uusd")],
+                }),
+                Asset::cw20(Addr::unchecked("mock_token"), 88888u128)
+                    .transfer_msg("alice")
+                    .unwrap(),
+            ]
+        );
+    }
+
+    #[test]
+    fn transferring_balances_after_tax() {
+        let mut deps = mock_dependencies();
+        deps.querier.set_native_tax_rate(Decimal::permille(1));
+        deps.querier.set_native_tax_cap("uusd", 1_000_000);
+        deps.querier
+            .set_base_balances("contract", &[Coin::new(2_002_000u128, "uusd")]);
+        deps.querier
+            .set_cw20_balance("mock_token", "contract", 12345);
+        let querier = deps.as_ref().querier;
+
+        let balances = AssetList::query_asset_info_balances(
+            vec![
+                AssetInfo::native("uusd"),
+                AssetInfo::cw20(Addr::unchecked("mock_token")),
+            ],
+            &querier,
+            &Addr::unchecked("contract"),
+        )
+        .unwrap();
+        let msgs = balances.transfer_msgs_after_tax(&querier, "alice").unwrap();
+        assert_eq!(
+            msgs,
+            vec![
+                Asset::native("uusd", 2_000_000u128)
+                    .transfer_msg("alice")
+                    .unwrap(),
+                Asset::cw20(Addr::unchecked("mock_token"), 12345u128)
+                    .transfer_msg("alice")
+                    .unwrap(),
+            ]
+        );
+    }
+}
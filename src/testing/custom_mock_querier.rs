@@ -1,10 +1,10 @@
+use crate::terra::TerraQueryWrapper;
 use cosmwasm_std::testing::MockQuerier;
 use cosmwasm_std::{
-    from_binary, from_slice, Addr, Coin, Decimal, Querier, QuerierResult, QueryRequest, StdResult,
-    SystemError, WasmQuery,
+    from_json, Addr, Coin, Decimal, Querier, QuerierResult, QueryRequest, StdResult, SystemError,
+    WasmQuery,
 };
 use cw20::Cw20QueryMsg;
-use terra_cosmwasm::TerraQueryWrapper;
 
 use super::cw20_querier::Cw20Querier;
 use super::native_querier::NativeQuerier;
@@ -27,7 +27,7 @@ impl Default for CustomMockQuerier {
 
 impl Querier for CustomMockQuerier {
     fn raw_query(&self, bin_request: &[u8]) -> QuerierResult {
-        let request: QueryRequest<TerraQueryWrapper> = match from_slice(bin_request) {
+        let request: QueryRequest<TerraQueryWrapper> = match from_json(bin_request) {
             Ok(v) => v,
             Err(e) => {
                 return Err(SystemError::InvalidRequest {
@@ -51,7 +51,7 @@ impl CustomMockQuerier {
             QueryRequest::Wasm(WasmQuery::Smart { contract_addr, msg }) => {
                 let contract_addr = Addr::unchecked(contract_addr);
 
-                let parse_cw20_query: StdResult<Cw20QueryMsg> = from_binary(msg);
+                let parse_cw20_query: StdResult<Cw20QueryMsg> = from_json(msg);
                 if let Ok(cw20_query) = parse_cw20_query {
                     return self.cw20_querier.handle_query(&contract_addr, cw20_query);
                 }
@@ -64,7 +64,10 @@ impl CustomMockQuerier {
     }
 
     pub fn set_base_balances(&mut self, address: &str, balances: &[Coin]) {
+        #[cfg(feature = "cosmwasm-1")]
         self.base.update_balance(address, balances.to_vec());
+        #[cfg(not(feature = "cosmwasm-1"))]
+        self.base.bank.update_balance(address, balances.to_vec());
     }
 
     pub fn set_cw20_balance(&mut self, contract: &str, user: &str, balance: u128) {
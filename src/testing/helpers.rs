@@ -1,12 +1,40 @@
+#[cfg(feature = "terra-classic")]
+use std::marker::PhantomData;
+
+#[cfg(feature = "terra-classic")]
 use cosmwasm_std::testing::{MockApi, MockStorage};
+#[cfg(feature = "terra-classic")]
 use cosmwasm_std::OwnedDeps;
+use cosmwasm_std::{Binary, ContractResult, QuerierResult, SystemError, SystemResult};
 
+#[cfg(feature = "terra-classic")]
 use super::custom_mock_querier::CustomMockQuerier;
 
+#[cfg(feature = "terra-classic")]
 pub fn mock_dependencies() -> OwnedDeps<MockStorage, MockApi, CustomMockQuerier> {
     OwnedDeps {
         storage: MockStorage::default(),
         api: MockApi::default(),
         querier: CustomMockQuerier::default(),
+        custom_query_type: PhantomData,
     }
 }
+
+/// The result of a `WasmQuery::ContractInfo` query for an existing contract
+///
+/// `ContractInfoResponse` is non-exhaustive in cosmwasm-std 2.x, so the
+/// response is written as raw JSON.
+pub fn contract_info_result() -> QuerierResult {
+    SystemResult::Ok(ContractResult::Ok(Binary::from(
+        br#"{"code_id":1,"creator":"creator","admin":null,"pinned":false,"ibc_port":null}"#
+            .as_slice(),
+    )))
+}
+
+/// The result of a `WasmQuery::ContractInfo` query for an address without a
+/// contract
+pub fn no_such_contract_result(addr: &str) -> QuerierResult {
+    SystemResult::Err(SystemError::NoSuchContract {
+        addr: addr.to_string(),
+    })
+}
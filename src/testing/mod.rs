@@ -1,7 +1,11 @@
+#[cfg(feature = "terra-classic")]
 mod custom_mock_querier;
+#[cfg(feature = "terra-classic")]
 mod cw20_querier;
 mod helpers;
+#[cfg(feature = "terra-classic")]
 mod native_querier;
 
-pub use custom_mock_querier::CustomMockQuerier;
+#[cfg(feature = "terra-classic")]
 pub use helpers::mock_dependencies;
+pub use helpers::{contract_info_result, no_such_contract_result};
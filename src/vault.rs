@@ -0,0 +1,303 @@
+//! Deposit and redeem messages for vaults following the CosmWasm vault
+//! standard
+//!
+//! NOTE: The message types mirror those of `cw-vault-standard`, so that the
+//! crate does not need to depend on a specific version of it.
+
+use cosmwasm_std::{
+    to_json_binary, Addr, Coin, CosmosMsg, CustomQuery, QuerierWrapper, StdError, StdResult,
+    Uint128, WasmMsg,
+};
+use cw20::Cw20ExecuteMsg;
+
+use schemars::JsonSchema;
+use serde::{Deserialize, Serialize};
+
+use crate::{validate_native_denom, Asset, AssetInfo, AssetQuerier};
+
+/// The execute messages of the vault standard used by this module
+#[derive(Serialize, Deserialize, Clone, Debug, PartialEq, JsonSchema)]
+#[serde(rename_all = "snake_case")]
+pub enum VaultStandardExecuteMsg {
+    Deposit {
+        amount: Uint128,
+        recipient: Option<String>,
+    },
+    Redeem {
+        recipient: Option<String>,
+        amount: Uint128,
+    },
+}
+
+/// The query messages of the vault standard used by this module
+#[derive(Serialize, Deserialize, Clone, Debug, PartialEq, JsonSchema)]
+#[serde(rename_all = "snake_case")]
+pub enum VaultStandardQueryMsg {
+    Info {},
+}
+
+/// The response to `VaultStandardQueryMsg::Info`
+#[derive(Serialize, Deserialize, Clone, Debug, PartialEq, JsonSchema)]
+pub struct VaultInfoResponse {
+    /// The denom or CW20 contract address of the vault's underlying token
+    pub base_token: String,
+    /// The denom or CW20 contract address of the vault's share token
+    pub vault_token: String,
+}
+
+impl Asset {
+    /// Generate a message that deposits the asset into a vault, minting vault
+    /// shares to `recipient`, or to the sender if `None`
+    ///
+    /// Native coins are attached to a `Deposit` message as funds, while CW20
+    /// tokens are sent to the vault with a `Deposit` message as payload.
+    ///
+    /// **Usage:**
+    /// ```rust
+    /// let asset = Asset::native("uosmo", 12345u128);
+    /// let msg = asset.vault_deposit_msg("vault", None)?;
+    /// ```
+    pub fn vault_deposit_msg<A: Into<String>>(
+        &self,
+        vault_addr: A,
+        recipient: Option<String>,
+    ) -> StdResult<CosmosMsg> {
+        self.vault_deposit_msg_custom(vault_addr, recipient)
+    }
+
+    /// Like `vault_deposit_msg`, but for a contract using custom messages
+    pub fn vault_deposit_msg_custom<A: Into<String>, T>(
+        &self,
+        vault_addr: A,
+        recipient: Option<String>,
+    ) -> StdResult<CosmosMsg<T>> {
+        self.vault_msg(
+            vault_addr.into(),
+            VaultStandardExecuteMsg::Deposit {
+                amount: self.amount,
+                recipient,
+            },
+        )
+    }
+
+    /// Generate a message that redeems the vault shares represented by the
+    /// asset, sending the underlying tokens to `recipient`, or to the sender
+    /// if `None`
+    ///
+    /// Native share tokens are attached to a `Redeem` message as funds, while
+    /// CW20 share tokens are sent to the vault with a `Redeem` message as
+    /// payload.
+    pub fn vault_redeem_msg<A: Into<String>>(
+        &self,
+        vault_addr: A,
+        recipient: Option<String>,
+    ) -> StdResult<CosmosMsg> {
+        self.vault_redeem_msg_custom(vault_addr, recipient)
+    }
+
+    /// Like `vault_redeem_msg`, but for a contract using custom messages
+    pub fn vault_redeem_msg_custom<A: Into<String>, T>(
+        &self,
+        vault_addr: A,
+        recipient: Option<String>,
+    ) -> StdResult<CosmosMsg<T>> {
+        self.vault_msg(
+            vault_addr.into(),
+            VaultStandardExecuteMsg::Redeem {
+                recipient,
+                amount: self.amount,
+            },
+        )
+    }
+
+    fn vault_msg<T>(
+        &self,
+        vault_addr: String,
+        msg: VaultStandardExecuteMsg,
+    ) -> StdResult<CosmosMsg<T>> {
+        match &self.info {
+            AssetInfo::Native(denom) => Ok(CosmosMsg::Wasm(WasmMsg::Execute {
+                contract_addr: vault_addr,
+                msg: to_json_binary(&msg)?,
+                funds: vec![Coin {
+                    denom: denom.clone(),
+                    amount: self.amount,
+                }],
+            })),
+            AssetInfo::Cw20(contract_addr) => Ok(CosmosMsg::Wasm(WasmMsg::Execute {
+                contract_addr: contract_addr.into(),
+                msg: to_json_binary(&Cw20ExecuteMsg::Send {
+                    contract: vault_addr,
+                    amount: self.amount,
+                    msg: to_json_binary(&msg)?,
+                })?,
+                funds: vec![],
+            })),
+            AssetInfo::Cw721(..) => {
+                Err(StdError::generic_err("vaults do not support cw721 tokens"))
+            }
+        }
+    }
+}
+
+/// Query the underlying asset of a vault
+///
+/// The vault standard returns the underlying token as a plain string, so it is
+/// a CW20 token if it is an address with a contract at it, and a native coin
+/// if it is a valid denom otherwise. Failures of the contract query are
+/// returned as errors rather than taken to mean a native coin.
+pub fn query_vault_underlying<A: Into<String>, C: CustomQuery>(
+    querier: &QuerierWrapper<C>,
+    vault: A,
+) -> StdResult<AssetInfo> {
+    let res: VaultInfoResponse =
+        querier.query_wasm_smart(vault, &VaultStandardQueryMsg::Info {})?;
+    // chains reject contract queries for strings that are not addresses, so
+    // denoms are not queried
+    if bech32::decode(&res.base_token).is_ok() && querier.query_is_contract(&res.base_token)? {
+        return Ok(AssetInfo::Cw20(Addr::unchecked(res.base_token)));
+    }
+    validate_native_denom(&res.base_token)?;
+    Ok(AssetInfo::Native(res.base_token))
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::testing::{contract_info_result, no_such_contract_result};
+    use cosmwasm_std::testing::{MockApi, MockQuerier};
+    use cosmwasm_std::{from_json, Binary, ContractResult, SystemError, SystemResult, WasmQuery};
+
+    fn mock_querier(base_token: &str, token: &Addr, wallet: &Addr) -> MockQuerier {
+        let (base_token, token, wallet) = (base_token.to_string(), token.clone(), wallet.clone());
+        let mut querier: MockQuerier = MockQuerier::new(&[]);
+        querier.update_wasm(move |query| match query {
+            WasmQuery::Smart { contract_addr, msg } if contract_addr == "vault" => {
+                let res = match from_json(msg).unwrap() {
+                    VaultStandardQueryMsg::Info {} => to_json_binary(&VaultInfoResponse {
+                        base_token: base_token.clone(),
+                        vault_token: "factory/vault/shares".to_string(),
+                    }),
+                };
+                SystemResult::Ok(ContractResult::Ok(res.unwrap()))
+            }
+            WasmQuery::ContractInfo { contract_addr } if contract_addr == token.as_str() => {
+                contract_info_result()
+            }
+            WasmQuery::ContractInfo { contract_addr } if contract_addr == wallet.as_str() => {
+                no_such_contract_result(contract_addr)
+            }
+            WasmQuery::ContractInfo { .. } => SystemResult::Err(SystemError::Unknown {}),
+            _ => panic!("[mock]: unsupported wasm query {:?}", query),
+        });
+        querier
+    }
+
+    #[test]
+    fn depositing() {
+        let asset = Asset::native("uosmo", 12345u128);
+        let msg = asset.vault_deposit_msg("vault", None).unwrap();
+        assert_eq!(
+            msg,
+            CosmosMsg::Wasm(WasmMsg::Execute {
+                contract_addr: "vault".to_string(),
+                msg: to_json_binary(&VaultStandardExecuteMsg::Deposit {
+                    amount: Uint128::new(12345),
+                    recipient: None,
+                })
+                .unwrap(),
+                funds: vec![Coin::new(12345u128, "uosmo")],
+            })
+        );
+
+        let asset = Asset::cw20(Addr::unchecked("mock_token"), 12345u128);
+        let msg = asset
+            .vault_deposit_msg("vault", Some("alice".to_string()))
+            .unwrap();
+        assert_eq!(
+            msg,
+            CosmosMsg::Wasm(WasmMsg::Execute {
+                contract_addr: "mock_token".to_string(),
+                msg: to_json_binary(&Cw20ExecuteMsg::Send {
+                    contract: "vault".to_string(),
+                    amount: Uint128::new(12345),
+                    msg: to_json_binary(&VaultStandardExecuteMsg::Deposit {
+                        amount: Uint128::new(12345),
+                        recipient: Some("alice".to_string()),
+                    })
+                    .unwrap(),
+                })
+                .unwrap(),
+                funds: vec![],
+            })
+        );
+
+        let asset = Asset::cw721(Addr::unchecked("mock_nft"), "42");
+        assert_eq!(
+            asset.vault_deposit_msg("vault", None),
+            Err(StdError::generic_err("vaults do not support cw721 tokens"))
+        );
+    }
+
+    #[test]
+    fn redeeming() {
+        let shares = Asset::native("factory/vault/shares", 1000u128);
+        let msg = shares
+            .vault_redeem_msg("vault", Some("alice".to_string()))
+            .unwrap();
+        assert_eq!(
+            msg,
+            CosmosMsg::Wasm(WasmMsg::Execute {
+                contract_addr: "vault".to_string(),
+                msg: Binary::from(
+                    br#"{"redeem":{"recipient":"alice","amount":"1000"}}"#.as_slice()
+                ),
+                funds: vec![Coin::new(1000u128, "factory/vault/shares")],
+            })
+        );
+
+        let shares = Asset::cw20(Addr::unchecked("vault_token"), 1000u128);
+        let msg = shares.vault_redeem_msg("vault", None).unwrap();
+        assert_eq!(
+            msg,
+            CosmosMsg::Wasm(WasmMsg::Execute {
+                contract_addr: "vault_token".to_string(),
+                msg: to_json_binary(&Cw20ExecuteMsg::Send {
+                    contract: "vault".to_string(),
+                    amount: Uint128::new(1000),
+                    msg: to_json_binary(&VaultStandardExecuteMsg::Redeem {
+                        recipient: None,
+                        amount: Uint128::new(1000),
+                    })
+                    .unwrap(),
+                })
+                .unwrap(),
+                funds: vec![],
+            })
+        );
+    }
+
+    #[test]
+    fn querying_underlying() {
+        let api = MockApi::default();
+        let token = api.addr_make("mock_token");
+        let wallet = api.addr_make("alice");
+        let query = |base_token: &str| {
+            let querier = mock_querier(base_token, &token, &wallet);
+            let querier: QuerierWrapper = QuerierWrapper::new(&querier);
+            query_vault_underlying(&querier, "vault")
+        };
+
+        assert_eq!(query("uosmo"), Ok(AssetInfo::native("uosmo")));
+        assert_eq!(query(token.as_str()), Ok(AssetInfo::cw20(token.clone())));
+
+        // an address without a contract is only a native coin if it is a
+        // valid denom, while other failures of the contract query are returned
+        assert_eq!(
+            query(wallet.as_str()),
+            Ok(AssetInfo::native(wallet.as_str()))
+        );
+        assert!(query(api.addr_make("bob").as_str()).is_err());
+        assert!(query("u osmo").is_err());
+    }
+}